@@ -1,5 +1,11 @@
 // https://adventofcode.com/2022/day/2
 
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
 pub fn calculate_score(rounds: Vec<Round>) -> i32 {
     rounds
         .iter()
@@ -46,68 +52,108 @@ mod tests {
         assert_eq!(13509, score);
     }
 
+    #[test]
+    fn parses_and_displays_round_in_guide_notation() {
+        let round: Round = "C X".parse().unwrap();
+
+        assert_eq!(Round::new(Choice::Scissors, Choice::Rock), round);
+        assert_eq!(RoundResult::Win, round.get_result());
+        assert_eq!(7, round.get_score());
+        assert_eq!("C X", round.to_string());
+    }
+
+    #[test]
+    fn fails_to_parse_malformed_round() {
+        assert_eq!(Err(ParseRoundError::InvalidOpponentsChoice('D')), "D X".parse::<Round>());
+        assert_eq!(Err(ParseRoundError::InvalidYourChoice('A')), "A A".parse::<Round>());
+        assert_eq!(Err(ParseRoundError::InvalidFormat("AX".to_string())), "AX".parse::<Round>());
+    }
+
+    #[test]
+    fn builds_round_from_desired_result() {
+        let round = Round::from_result(Choice::Rock, RoundResult::Lose);
+
+        assert_eq!(Choice::Scissors, round.your_choice());
+        assert_eq!(RoundResult::Lose, round.get_result());
+    }
+
+    #[test]
+    fn round_trips_round_through_serde() {
+        let round = Round::new(Choice::Paper, Choice::Rock);
+
+        let json = serde_json::to_string(&round).unwrap();
+
+        assert_eq!(r#"{"opponents_choice":"Paper","your_choice":"Rock"}"#, json);
+        assert_eq!(round, serde_json::from_str(&json).unwrap());
+    }
+
     fn parse_input_incorrectly(input: &str) -> Vec<Round> {
         input
             .lines()
-            .map(|line| {
-                let mut chars = line.chars();
-                Round {
-                    opponents_choice: get_choice_naively(chars.nth(0).unwrap()),
-                    your_choice: get_choice_naively(chars.nth(1).unwrap())
-                }
-            })
+            .map(|line| line.parse().unwrap())
             .collect()
     }
 
-    fn get_choice_naively(char: char) -> Choice {
-        match char {
-            'A' | 'X' => Choice::Rock,
-            'B' | 'Y' => Choice::Paper,
-            'C' | 'Z' => Choice::Scissors,
-            _ => panic!("Cannot map '{}' to a choice", char)
-        }
-    }
-
     fn parse_input_correctly(input: &str) -> Vec<Round> {
         input
             .lines()
             .map(|line| {
                 let mut chars = line.chars();
-                let opponents_choice = get_choice_naively(chars.nth(0).unwrap());
-                let your_choice = match (opponents_choice, chars.nth(1).unwrap()) {
-                    (Choice::Rock, 'X') => Choice::Scissors,
-                    (Choice::Rock, 'Y') => Choice::Rock,
-                    (Choice::Rock, 'Z') => Choice::Paper,
-                    (Choice::Paper, 'X') => Choice::Rock,
-                    (Choice::Paper, 'Y') => Choice::Paper,
-                    (Choice::Paper, 'Z') => Choice::Scissors,
-                    (Choice::Scissors, 'X') => Choice::Paper,
-                    (Choice::Scissors, 'Y') => Choice::Scissors,
-                    (Choice::Scissors, 'Z') => Choice::Rock,
-                    (_, char) => panic!("Could not parse input for your choice when given char {}", char)
+                let opponents_choice = Choice::from_opponents_code(chars.next().unwrap()).unwrap();
+                let desired_result = match chars.nth(1).unwrap() {
+                    'X' => RoundResult::Lose,
+                    'Y' => RoundResult::Draw,
+                    'Z' => RoundResult::Win,
+                    char => panic!("Could not parse input for the desired result when given char {}", char)
                 };
-                Round {
-                    opponents_choice,
-                    your_choice
-                }
+                Round::from_result(opponents_choice, desired_result)
             })
             .collect()
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Round {
     opponents_choice: Choice,
     your_choice: Choice
 }
 
 impl Round {
-    fn get_score(&self) -> i32 {
+    pub fn new(opponents_choice: Choice, your_choice: Choice) -> Self {
+        Round {
+            opponents_choice,
+            your_choice
+        }
+    }
+
+    pub fn from_result(opponents_choice: Choice, result: RoundResult) -> Self {
+        let your_choice = match (opponents_choice, result) {
+            (Choice::Rock, RoundResult::Win) => Choice::Paper,
+            (Choice::Rock, RoundResult::Lose) => Choice::Scissors,
+            (Choice::Paper, RoundResult::Win) => Choice::Scissors,
+            (Choice::Paper, RoundResult::Lose) => Choice::Rock,
+            (Choice::Scissors, RoundResult::Win) => Choice::Rock,
+            (Choice::Scissors, RoundResult::Lose) => Choice::Paper,
+            (choice, RoundResult::Draw) => choice
+        };
+        Round::new(opponents_choice, your_choice)
+    }
+
+    pub fn opponents_choice(&self) -> Choice {
+        self.opponents_choice
+    }
+
+    pub fn your_choice(&self) -> Choice {
+        self.your_choice
+    }
+
+    pub fn get_score(&self) -> i32 {
         let choice_points = self.your_choice as i32;
         let result_points = self.get_result() as i32;
         choice_points + result_points
     }
 
-    fn get_result(&self) -> RoundResult {
+    pub fn get_result(&self) -> RoundResult {
         match (self.your_choice, self.opponents_choice) {
             (Choice::Rock, Choice::Scissors) => RoundResult::Win,
             (Choice::Rock, Choice::Paper) => RoundResult::Lose,
@@ -120,13 +166,105 @@ impl Round {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, PartialOrd)]
+impl FromStr for Round {
+    type Err = ParseRoundError;
+
+    // Reads the guide notation where the opponent plays A, B or C and you play X, Y or Z
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (opponents_code, your_code) = match value.trim().split_once(' ') {
+            Some((opponents_code, your_code)) => (opponents_code, your_code),
+            None => return Err(ParseRoundError::InvalidFormat(value.to_string()))
+        };
+        let opponents_code = to_single_char(opponents_code)
+            .ok_or_else(|| ParseRoundError::InvalidFormat(value.to_string()))?;
+        let your_code = to_single_char(your_code)
+            .ok_or_else(|| ParseRoundError::InvalidFormat(value.to_string()))?;
+        Ok(Round {
+            opponents_choice: Choice::from_opponents_code(opponents_code)
+                .ok_or(ParseRoundError::InvalidOpponentsChoice(opponents_code))?,
+            your_choice: Choice::from_your_code(your_code)
+                .ok_or(ParseRoundError::InvalidYourChoice(your_code))?
+        })
+    }
+}
+
+fn to_single_char(value: &str) -> Option<char> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(char), None) => Some(char),
+        _ => None
+    }
+}
+
+impl Display for Round {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.opponents_choice.opponents_code(), self.your_choice.your_code())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseRoundError {
+    InvalidFormat(String),
+    InvalidOpponentsChoice(char),
+    InvalidYourChoice(char)
+}
+
+impl Display for ParseRoundError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRoundError::InvalidFormat(value) => write!(f, "Expected a round like 'A Y' but found '{}'", value),
+            ParseRoundError::InvalidOpponentsChoice(char) => write!(f, "Cannot map '{}' to the opponent's choice", char),
+            ParseRoundError::InvalidYourChoice(char) => write!(f, "Cannot map '{}' to your choice", char)
+        }
+    }
+}
+
+impl Error for ParseRoundError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum Choice {
     Rock = 1,
     Paper = 2,
     Scissors = 3
 }
 
+impl Choice {
+    pub fn from_opponents_code(char: char) -> Option<Choice> {
+        match char {
+            'A' => Some(Choice::Rock),
+            'B' => Some(Choice::Paper),
+            'C' => Some(Choice::Scissors),
+            _ => None
+        }
+    }
+
+    pub fn from_your_code(char: char) -> Option<Choice> {
+        match char {
+            'X' => Some(Choice::Rock),
+            'Y' => Some(Choice::Paper),
+            'Z' => Some(Choice::Scissors),
+            _ => None
+        }
+    }
+
+    pub fn opponents_code(&self) -> char {
+        match self {
+            Choice::Rock => 'A',
+            Choice::Paper => 'B',
+            Choice::Scissors => 'C'
+        }
+    }
+
+    pub fn your_code(&self) -> char {
+        match self {
+            Choice::Rock => 'X',
+            Choice::Paper => 'Y',
+            Choice::Scissors => 'Z'
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RoundResult {
     Lose = 0,
     Draw = 3,
@@ -33,7 +33,7 @@ mod tests {
     fn parse_input(input: &str) -> Vec<SNAFU> {
         input
             .lines()
-            .map(|line| SNAFU::from(line))
+            .map(SNAFU::from)
             .collect()
     }
 }
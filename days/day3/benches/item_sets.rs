@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use day3::{calculate_double_packed_items_priority_sum, calculate_priority_sum_of_badges, AocPriorities, Item, PriorityScheme, Rucksack};
+
+const NUM_RUCKSACKS: usize = 30_000;
+const COMPARTMENT_SIZE: usize = 24;
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Small xorshift generator so the inputs are large but reproducible
+struct Generator(u64);
+
+impl Generator {
+    fn next_index(&mut self, len: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % len as u64) as usize
+    }
+
+    fn next_item(&mut self) -> Item {
+        Item::new(ITEMS[self.next_index(ITEMS.len())] as char).unwrap()
+    }
+
+    fn compartment(&mut self) -> Vec<Item> {
+        (0..COMPARTMENT_SIZE).map(|_| self.next_item()).collect()
+    }
+}
+
+fn generate_rucksacks() -> Vec<Rucksack> {
+    let mut generator = Generator(0x2022_1203);
+    (0..NUM_RUCKSACKS)
+        .map(|_| Rucksack::new(generator.compartment(), generator.compartment()).unwrap())
+        .collect()
+}
+
+// Each elf of a group packs from its own third of the remaining items so the badge is the only shared item
+fn generate_groups() -> Vec<Vec<Rucksack>> {
+    let mut generator = Generator(0x2022_1225);
+    (0..NUM_RUCKSACKS / 3)
+        .map(|_| {
+            let badge = generator.next_item();
+            let others: Vec<_> = ITEMS
+                .iter()
+                .map(|item| Item::new(*item as char).unwrap())
+                .filter(|item| *item != badge)
+                .collect();
+            others
+                .chunks(others.len() / 3)
+                .take(3)
+                .map(|allowed| {
+                    let mut items: Vec<_> = (0..COMPARTMENT_SIZE * 2 - 1)
+                        .map(|_| allowed[generator.next_index(allowed.len())])
+                        .collect();
+                    items.insert(generator.next_index(items.len()), badge);
+                    let second_compartment = items.split_off(COMPARTMENT_SIZE);
+                    Rucksack::new(items, second_compartment).unwrap()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn hash_set_double_packed_sum(rucksacks: Vec<Rucksack>) -> u32 {
+    rucksacks
+        .iter()
+        .map(|rucksack| {
+            let first: HashSet<_> = rucksack.first_compartment().iter().collect();
+            let second: HashSet<_> = rucksack.second_compartment().iter().collect();
+            first.intersection(&second).map(|item| AocPriorities.priority(**item).unwrap()).sum::<u32>()
+        })
+        .sum()
+}
+
+fn hash_set_badge_sum(rucksack_groups: Vec<Vec<Rucksack>>) -> u32 {
+    rucksack_groups
+        .iter()
+        .map(|group| group
+            .iter()
+            .map(|rucksack| rucksack.first_compartment()
+                .iter()
+                .chain(rucksack.second_compartment())
+                .collect::<HashSet<_>>()
+            )
+            .reduce(|acc, curr| acc.intersection(&curr).copied().collect())
+            .unwrap()
+            .into_iter()
+            .map(|item| AocPriorities.priority(*item).unwrap())
+            .sum::<u32>()
+        )
+        .sum()
+}
+
+fn double_packed_items(c: &mut Criterion) {
+    let rucksacks = generate_rucksacks();
+    assert_eq!(
+        hash_set_double_packed_sum(rucksacks.clone()),
+        calculate_double_packed_items_priority_sum(rucksacks.clone())
+    );
+
+    let mut group = c.benchmark_group("double_packed_items");
+    group.bench_function("hash_set", |b| b.iter_batched(
+        || rucksacks.clone(),
+        |rucksacks| hash_set_double_packed_sum(black_box(rucksacks)),
+        BatchSize::LargeInput
+    ));
+    group.bench_function("item_set", |b| b.iter_batched(
+        || rucksacks.clone(),
+        |rucksacks| calculate_double_packed_items_priority_sum(black_box(rucksacks)),
+        BatchSize::LargeInput
+    ));
+    group.finish();
+}
+
+fn badges(c: &mut Criterion) {
+    let groups = generate_groups();
+    assert_eq!(hash_set_badge_sum(groups.clone()), calculate_priority_sum_of_badges(groups.clone()));
+
+    let mut group = c.benchmark_group("badges");
+    group.bench_function("hash_set", |b| b.iter_batched(
+        || groups.clone(),
+        |groups| hash_set_badge_sum(black_box(groups)),
+        BatchSize::LargeInput
+    ));
+    group.bench_function("item_set", |b| b.iter_batched(
+        || groups.clone(),
+        |groups| calculate_priority_sum_of_badges(black_box(groups)),
+        BatchSize::LargeInput
+    ));
+    group.finish();
+}
+
+criterion_group!(benches, double_packed_items, badges);
+criterion_main!(benches);
@@ -0,0 +1,222 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use crate::priority_scheme;
+use crate::{AocPriorities, Item, ItemSet, PriorityScheme, Rucksack, UnscoredItemError};
+
+pub fn find_badges(rucksacks: &[Rucksack], group_size: usize, on_error: OnBadgeError) -> Result<BadgeReport, BadgeError> {
+    if group_size == 0 {
+        return Err(BadgeError::InvalidGroupSize)
+    }
+    let mut report = BadgeReport::default();
+    for (group_index, group) in rucksacks.chunks(group_size).enumerate() {
+        let first_rucksack_index = group_index * group_size;
+        let result = if group.len() != group_size {
+            Err(BadgeError::IncompleteGroup {
+                group_index,
+                size: group.len(),
+                expected_size: group_size
+            })
+        } else {
+            find_badge(group, group_index, first_rucksack_index)
+        };
+        match (result, on_error) {
+            (Ok(badge), _) => report.badges.push(GroupBadge { group_index, badge }),
+            (Err(error), OnBadgeError::Stop) => return Err(error),
+            (Err(error), OnBadgeError::Continue) => report.errors.push(error)
+        }
+    }
+    Ok(report)
+}
+
+pub(crate) fn find_badge(group: &[Rucksack], group_index: usize, first_rucksack_index: usize) -> Result<Item, BadgeError> {
+    let common_items = group
+        .iter()
+        .map(|rucksack| rucksack.items())
+        .reduce(|acc, curr| acc & curr)
+        .unwrap_or_default();
+    match common_items.len() {
+        1 => Ok(common_items.iter().next().unwrap()),
+        0 => {
+            let closest_item = find_most_shared_item(group);
+            let disagreeing_rucksacks = match closest_item {
+                Some(item) => group
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, rucksack)| !rucksack.items().contains(item))
+                    .map(|(i, _)| first_rucksack_index + i)
+                    .collect(),
+                None => (first_rucksack_index..first_rucksack_index + group.len()).collect()
+            };
+            Err(BadgeError::NoCommonItem {
+                group_index,
+                closest_item,
+                disagreeing_rucksacks
+            })
+        },
+        _ => Err(BadgeError::MultipleCandidates {
+            group_index,
+            candidates: common_items
+        })
+    }
+}
+
+// Ties go to the lowest item so diagnostics are deterministic
+fn find_most_shared_item(group: &[Rucksack]) -> Option<Item> {
+    let mut counts = BTreeMap::new();
+    for rucksack in group {
+        for item in rucksack.items() {
+            *counts.entry(item).or_insert(0) += 1
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|(item, count)| (*count, Reverse(*item)))
+        .map(|(item, _)| item)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OnBadgeError {
+    Stop,
+    Continue
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GroupBadge {
+    pub group_index: usize,
+    pub badge: Item
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BadgeReport {
+    pub badges: Vec<GroupBadge>,
+    pub errors: Vec<BadgeError>
+}
+
+impl BadgeReport {
+    pub fn priority_sum(&self) -> u32 {
+        self.priority_sum_with(&AocPriorities).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn priority_sum_with(&self, scheme: &impl PriorityScheme) -> Result<u32, UnscoredItemError> {
+        self.badges
+            .iter()
+            .map(|group_badge| priority_scheme::score(scheme, group_badge.badge))
+            .sum()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+// Rucksack indices point into the slice given to find_badges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BadgeError {
+    InvalidGroupSize,
+    IncompleteGroup { group_index: usize, size: usize, expected_size: usize },
+    NoCommonItem { group_index: usize, closest_item: Option<Item>, disagreeing_rucksacks: Vec<usize> },
+    MultipleCandidates { group_index: usize, candidates: ItemSet }
+}
+
+impl Display for BadgeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BadgeError::InvalidGroupSize => write!(f, "Groups must contain at least one rucksack"),
+            BadgeError::IncompleteGroup { group_index, size, expected_size } => write!(
+                f,
+                "Group {} only has {} rucksacks but {} were expected",
+                group_index,
+                size,
+                expected_size
+            ),
+            BadgeError::NoCommonItem { group_index, closest_item: Some(item), disagreeing_rucksacks } => write!(
+                f,
+                "Group {} has no item in common, rucksacks {:?} are missing '{}' which the others share",
+                group_index,
+                disagreeing_rucksacks,
+                item
+            ),
+            BadgeError::NoCommonItem { group_index, closest_item: None, .. } => write!(f, "Group {} has no items at all", group_index),
+            BadgeError::MultipleCandidates { group_index, candidates } => write!(
+                f,
+                "Group {} has {} items in common so the badge is ambiguous: {:?}",
+                group_index,
+                candidates.len(),
+                candidates
+            )
+        }
+    }
+}
+
+impl Error for BadgeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_rucksacks;
+    use crate::tests::item;
+
+    #[test]
+    fn finds_badges_in_example_input() {
+        let rucksacks = parse_rucksacks(include_str!("example.txt")).unwrap();
+
+        let report = find_badges(&rucksacks, 3, OnBadgeError::Stop).unwrap();
+
+        assert_eq!(70, report.priority_sum());
+    }
+
+    #[test]
+    fn finds_badges_with_other_group_sizes() {
+        let rucksacks = parse_rucksacks("abcd\nbxyz\naebf\nbgch").unwrap();
+
+        let pairs = find_badges(&rucksacks, 2, OnBadgeError::Stop).unwrap();
+        let everyone = find_badges(&rucksacks, 4, OnBadgeError::Stop).unwrap();
+
+        assert_eq!(vec![GroupBadge { group_index: 0, badge: item('b') }, GroupBadge { group_index: 1, badge: item('b') }], pairs.badges);
+        assert_eq!(vec![GroupBadge { group_index: 0, badge: item('b') }], everyone.badges);
+    }
+
+    #[test]
+    fn reports_rucksacks_disagreeing_on_badge() {
+        let rucksacks = parse_rucksacks("abcd\naxyz\nefgh").unwrap();
+
+        let result = find_badges(&rucksacks, 3, OnBadgeError::Stop);
+
+        assert_eq!(
+            Err(BadgeError::NoCommonItem { group_index: 0, closest_item: Some(item('a')), disagreeing_rucksacks: vec![2] }),
+            result
+        );
+    }
+
+    #[test]
+    fn continues_past_bad_groups_with_partial_results() {
+        let rucksacks = parse_rucksacks("abcd\nbxyz\nbfgh\nabcd\nbxcz\nbcgh\nabcd").unwrap();
+
+        let report = find_badges(&rucksacks, 3, OnBadgeError::Continue).unwrap();
+
+        assert_eq!(vec![GroupBadge { group_index: 0, badge: item('b') }], report.badges);
+        assert_eq!(
+            vec![
+                BadgeError::MultipleCandidates { group_index: 1, candidates: [item('b'), item('c')].iter().collect() },
+                BadgeError::IncompleteGroup { group_index: 2, size: 1, expected_size: 3 }
+            ],
+            report.errors
+        );
+        assert!(!report.is_complete());
+    }
+
+    #[test]
+    fn stops_at_first_bad_group() {
+        let rucksacks = parse_rucksacks("abcd\nbxyz\nefgh\nabcd\nbxcz\nbcgh").unwrap();
+
+        let result = find_badges(&rucksacks, 2, OnBadgeError::Stop);
+
+        assert_eq!(
+            Err(BadgeError::NoCommonItem { group_index: 1, closest_item: Some(item('a')), disagreeing_rucksacks: vec![2] }),
+            result
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{Item, ItemSet, Rucksack};
+
+pub fn discover_badge_groups(rucksacks: &[Rucksack], max_steps: Option<usize>) -> BadgeGroupDiscovery {
+    if !rucksacks.len().is_multiple_of(3) {
+        return BadgeGroupDiscovery::Impossible
+    }
+    let mut search = Search::new(rucksacks, max_steps);
+    search.run();
+    let mut solutions = search.solutions.into_iter();
+    match (search.limit_reached, solutions.next(), solutions.next()) {
+        (true, first, _) => BadgeGroupDiscovery::Inconclusive(first),
+        (false, None, _) => BadgeGroupDiscovery::Impossible,
+        (false, Some(first), None) => BadgeGroupDiscovery::Unique(first),
+        (false, Some(first), Some(second)) => BadgeGroupDiscovery::Ambiguous(first, second)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BadgeGroup {
+    pub rucksack_indices: [usize; 3],
+    pub badge: Item
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BadgeGroupDiscovery {
+    Unique(Vec<BadgeGroup>),
+    // Two different partitions prove the grouping can't be recovered from the items alone
+    Ambiguous(Vec<BadgeGroup>, Vec<BadgeGroup>),
+    Impossible,
+    // The step limit ran out before uniqueness could be decided
+    Inconclusive(Option<Vec<BadgeGroup>>)
+}
+
+impl BadgeGroupDiscovery {
+    pub fn groups(&self) -> Option<&[BadgeGroup]> {
+        match self {
+            BadgeGroupDiscovery::Unique(groups)
+            | BadgeGroupDiscovery::Ambiguous(groups, _)
+            | BadgeGroupDiscovery::Inconclusive(Some(groups)) => Some(groups),
+            _ => None
+        }
+    }
+
+    pub fn is_unique(&self) -> bool {
+        matches!(self, BadgeGroupDiscovery::Unique(_))
+    }
+}
+
+// An exact cover search over every triple of rucksacks sharing exactly one item.
+// Triples are killed as soon as one of their rucksacks is grouped so the number
+// of live triples per rucksack is always known and the search can branch on the
+// most constrained rucksack, backing out as soon as one can no longer be grouped.
+struct Search {
+    triples: Vec<BadgeGroup>,
+    triples_by_rucksack: Vec<Vec<usize>>,
+    kills: Vec<u8>,
+    live_triple_counts: Vec<usize>,
+    assigned: Vec<bool>,
+    current: Vec<BadgeGroup>,
+    solutions: Vec<Vec<BadgeGroup>>,
+    steps: usize,
+    max_steps: Option<usize>,
+    limit_reached: bool
+}
+
+impl Search {
+    fn new(rucksacks: &[Rucksack], max_steps: Option<usize>) -> Self {
+        let items: Vec<ItemSet> = rucksacks.iter().map(|rucksack| rucksack.items()).collect();
+        let mut triples = Vec::new();
+        let mut triples_by_rucksack = vec![Vec::new(); items.len()];
+        for a in 0..items.len() {
+            for b in a + 1..items.len() {
+                let shared_items = &items[a] & &items[b];
+                if shared_items.is_empty() {
+                    continue
+                }
+                for (c, third_items) in items.iter().enumerate().skip(b + 1) {
+                    let common_items = &shared_items & third_items;
+                    if common_items.len() == 1 {
+                        for rucksack in [a, b, c] {
+                            triples_by_rucksack[rucksack].push(triples.len())
+                        }
+                        triples.push(BadgeGroup {
+                            rucksack_indices: [a, b, c],
+                            badge: common_items.iter().next().unwrap()
+                        })
+                    }
+                }
+            }
+        }
+        Search {
+            kills: vec![0; triples.len()],
+            live_triple_counts: triples_by_rucksack.iter().map(|triples| triples.len()).collect(),
+            assigned: vec![false; items.len()],
+            triples,
+            triples_by_rucksack,
+            current: Vec::new(),
+            solutions: Vec::new(),
+            steps: 0,
+            max_steps,
+            limit_reached: false
+        }
+    }
+
+    // Returns false once searching further can't change the outcome
+    fn run(&mut self) -> bool {
+        self.steps += 1;
+        if self.max_steps.is_some_and(|max_steps| self.steps > max_steps) {
+            self.limit_reached = true;
+            return false
+        }
+
+        let Some(rucksack) = (0..self.assigned.len())
+            .filter(|i| !self.assigned[*i])
+            .min_by_key(|i| self.live_triple_counts[*i])
+        else {
+            self.solutions.push(self.current.clone());
+            return self.solutions.len() < 2
+        };
+
+        let live_triples: Vec<_> = self.triples_by_rucksack[rucksack]
+            .iter()
+            .filter(|triple| self.kills[**triple] == 0)
+            .copied()
+            .collect();
+        for triple in live_triples {
+            self.choose(triple);
+            let should_continue = self.run();
+            self.unchoose(triple);
+            if !should_continue {
+                return false
+            }
+        }
+        true
+    }
+
+    fn choose(&mut self, triple: usize) {
+        let group = self.triples[triple];
+        for rucksack in group.rucksack_indices {
+            self.assigned[rucksack] = true;
+            for other_triple in &self.triples_by_rucksack[rucksack] {
+                if self.kills[*other_triple] == 0 {
+                    for member in self.triples[*other_triple].rucksack_indices {
+                        self.live_triple_counts[member] -= 1
+                    }
+                }
+                self.kills[*other_triple] += 1
+            }
+        }
+        self.current.push(group)
+    }
+
+    fn unchoose(&mut self, triple: usize) {
+        let group = self.current.pop().unwrap();
+        debug_assert_eq!(self.triples[triple], group);
+        for rucksack in group.rucksack_indices.into_iter().rev() {
+            for other_triple in self.triples_by_rucksack[rucksack].iter().rev() {
+                self.kills[*other_triple] -= 1;
+                if self.kills[*other_triple] == 0 {
+                    for member in self.triples[*other_triple].rucksack_indices {
+                        self.live_triple_counts[member] += 1
+                    }
+                }
+            }
+            self.assigned[rucksack] = false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_rucksacks;
+
+    #[test]
+    fn discovers_unique_groups_in_shuffled_rucksacks() {
+        let rucksacks = parse_rucksacks("abcd\nefgh\nbxyz\nehij\nbklm\neopq").unwrap();
+
+        let discovery = discover_badge_groups(&rucksacks, None);
+
+        assert_eq!(
+            BadgeGroupDiscovery::Unique(vec![
+                BadgeGroup { rucksack_indices: [0, 2, 4], badge: Item::new('b').unwrap() },
+                BadgeGroup { rucksack_indices: [1, 3, 5], badge: Item::new('e').unwrap() }
+            ]),
+            discovery
+        );
+    }
+
+    #[test]
+    fn reports_impossible_groups() {
+        let rucksacks = parse_rucksacks("ab\nab\nab\nab\nab\nab").unwrap();
+
+        let discovery = discover_badge_groups(&rucksacks, None);
+
+        assert_eq!(BadgeGroupDiscovery::Impossible, discovery);
+    }
+
+    #[test]
+    fn reports_ambiguous_groups() {
+        let rucksacks = parse_rucksacks("ax\nay\naz\nax\nay\naz").unwrap();
+
+        let discovery = discover_badge_groups(&rucksacks, None);
+
+        assert!(matches!(discovery, BadgeGroupDiscovery::Ambiguous(_, _)));
+    }
+
+    #[test]
+    fn discovers_groups_in_shuffled_start_of_actual_input() {
+        let rucksacks = shuffle(parse_rucksacks(include_str!("actual.txt")).unwrap()[..9].to_vec());
+
+        let discovery = discover_badge_groups(&rucksacks, Some(100_000));
+
+        let BadgeGroupDiscovery::Unique(groups) = discovery else {
+            panic!("Expected a unique grouping, got {:?}", discovery)
+        };
+        assert_eq!(known_groups(&rucksacks), sorted(groups));
+    }
+
+    #[test]
+    fn finds_the_shuffled_actual_input_ambiguous() {
+        let rucksacks = shuffle(parse_rucksacks(include_str!("actual.txt")).unwrap());
+
+        let discovery = discover_badge_groups(&rucksacks, Some(100_000));
+
+        let BadgeGroupDiscovery::Ambiguous(first, second) = discovery else {
+            panic!("Expected an ambiguous grouping, got {:?}", discovery)
+        };
+        assert_ne!(sorted(first.clone()), sorted(second.clone()));
+        for groups in [known_groups(&rucksacks), first, second] {
+            assert_valid_grouping(&rucksacks, &groups)
+        }
+    }
+
+    fn shuffle(mut rucksacks: Vec<Rucksack>) -> Vec<Rucksack> {
+        rucksacks.reverse();
+        rucksacks.rotate_left(7);
+        rucksacks
+    }
+
+    // Undoes the shuffle to find where each of the puzzle's groups of three ended up
+    fn known_groups(rucksacks: &[Rucksack]) -> Vec<BadgeGroup> {
+        let num_rucksacks = rucksacks.len();
+        let shuffled_index = |i: usize| (2 * num_rucksacks - 1 - i - 7) % num_rucksacks;
+        let groups = (0..num_rucksacks / 3)
+            .map(|group| {
+                let mut rucksack_indices = [3 * group, 3 * group + 1, 3 * group + 2].map(shuffled_index);
+                rucksack_indices.sort();
+                let badge = common_items(rucksacks, &rucksack_indices).iter().next().unwrap();
+                BadgeGroup { rucksack_indices, badge }
+            })
+            .collect();
+        sorted(groups)
+    }
+
+    fn sorted(mut groups: Vec<BadgeGroup>) -> Vec<BadgeGroup> {
+        groups.sort_by_key(|group| group.rucksack_indices);
+        groups
+    }
+
+    fn assert_valid_grouping(rucksacks: &[Rucksack], groups: &[BadgeGroup]) {
+        let mut rucksack_indices: Vec<_> = groups.iter().flat_map(|group| group.rucksack_indices).collect();
+        rucksack_indices.sort();
+        assert_eq!((0..rucksacks.len()).collect::<Vec<_>>(), rucksack_indices);
+        for group in groups {
+            assert_eq!(vec![group.badge], common_items(rucksacks, &group.rucksack_indices).iter().collect::<Vec<_>>());
+        }
+    }
+
+    fn common_items(rucksacks: &[Rucksack], rucksack_indices: &[usize]) -> ItemSet {
+        rucksack_indices
+            .iter()
+            .map(|i| rucksacks[*i].items())
+            .reduce(|acc, curr| acc & curr)
+            .unwrap()
+    }
+}
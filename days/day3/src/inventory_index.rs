@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use crate::{Item, ItemSet, Rucksack};
+
+// Maps every item type to the rucksacks holding it so queries only touch the rucksacks that matter
+#[derive(Debug, Clone, Default)]
+pub struct InventoryIndex {
+    rucksacks_by_item: BTreeMap<Item, Vec<usize>>,
+    items_by_rucksack: Vec<ItemSet>
+}
+
+impl InventoryIndex {
+    pub fn new(rucksacks: &[Rucksack]) -> Self {
+        let mut index = InventoryIndex::default();
+        for rucksack in rucksacks {
+            index.insert(rucksack);
+        }
+        index
+    }
+
+    // Returns the index the rucksack can be queried by
+    pub fn insert(&mut self, rucksack: &Rucksack) -> usize {
+        let rucksack_index = self.items_by_rucksack.len();
+        let items = rucksack.items();
+        for item in items.iter() {
+            self.rucksacks_by_item.entry(item).or_default().push(rucksack_index)
+        }
+        self.items_by_rucksack.push(items);
+        rucksack_index
+    }
+
+    pub fn len(&self) -> usize {
+        self.items_by_rucksack.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items_by_rucksack.is_empty()
+    }
+
+    pub fn items_in(&self, rucksack_index: usize) -> Option<&ItemSet> {
+        self.items_by_rucksack.get(rucksack_index)
+    }
+
+    pub fn rucksacks_containing(&self, item: Item) -> &[usize] {
+        self.rucksacks_by_item
+            .get(&item)
+            .map(|rucksacks| rucksacks.as_slice())
+            .unwrap_or_default()
+    }
+
+    // Sorted from the most widespread item type down, ties broken by item
+    pub fn items_by_rucksack_count(&self) -> Vec<(Item, usize)> {
+        let mut counts: Vec<_> = self.rucksacks_by_item
+            .iter()
+            .map(|(item, rucksacks)| (*item, rucksacks.len()))
+            .collect();
+        counts.sort_by(|(a_item, a_count), (b_item, b_count)| b_count.cmp(a_count).then(a_item.cmp(b_item)));
+        counts
+    }
+
+    pub fn most_widespread_items(&self) -> Vec<(Item, usize)> {
+        let counts = self.items_by_rucksack_count();
+        let Some((_, max_count)) = counts.first().copied() else {
+            return counts
+        };
+        counts
+            .into_iter()
+            .take_while(|(_, count)| *count == max_count)
+            .collect()
+    }
+
+    pub fn rucksacks_sharing_with(&self, rucksack_index: usize, min_shared_items: usize) -> Vec<SharedItems> {
+        let mut shared_counts = vec![0; self.len()];
+        self.find_sharing(rucksack_index, min_shared_items, &mut shared_counts, |_| true)
+    }
+
+    pub fn pairs_sharing_at_least(&self, min_shared_items: usize) -> Vec<SharedItems> {
+        let mut shared_counts = vec![0; self.len()];
+        (0..self.len())
+            .flat_map(|rucksack_index| self.find_sharing(
+                rucksack_index,
+                min_shared_items,
+                &mut shared_counts,
+                |other| other > rucksack_index
+            ))
+            .collect()
+    }
+
+    // Counts shared items by walking the posting lists of the rucksack's items, only
+    // falling back to every rucksack when sharing nothing is enough to qualify
+    fn find_sharing(
+        &self,
+        rucksack_index: usize,
+        min_shared_items: usize,
+        shared_counts: &mut [usize],
+        include: impl Fn(usize) -> bool
+    ) -> Vec<SharedItems> {
+        let Some(items) = self.items_in(rucksack_index) else {
+            return Vec::new()
+        };
+        let candidates: Vec<usize> = if min_shared_items == 0 {
+            (0..self.len()).collect()
+        } else {
+            let mut candidates = Vec::new();
+            for item in items.iter() {
+                for other in self.rucksacks_containing(item) {
+                    if shared_counts[*other] == 0 {
+                        candidates.push(*other)
+                    }
+                    shared_counts[*other] += 1
+                }
+            }
+            candidates.sort_unstable();
+            candidates
+        };
+        let sharing = candidates
+            .iter()
+            .filter(|other| **other != rucksack_index && include(**other))
+            .filter(|other| min_shared_items == 0 || shared_counts[**other] >= min_shared_items)
+            .map(|other| SharedItems {
+                first_index: rucksack_index,
+                second_index: *other,
+                shared_items: items & &self.items_by_rucksack[*other]
+            })
+            .collect();
+        for other in candidates {
+            shared_counts[other] = 0
+        }
+        sharing
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedItems {
+    pub first_index: usize,
+    pub second_index: usize,
+    pub shared_items: ItemSet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_rucksacks;
+    use crate::tests::item;
+
+    #[test]
+    fn finds_rucksacks_containing_item() {
+        let index = InventoryIndex::new(&parse_rucksacks(include_str!("example.txt")).unwrap());
+
+        assert_eq!(&[0, 5], index.rucksacks_containing(item('p')));
+        assert!(index.rucksacks_containing(item('x')).is_empty());
+    }
+
+    #[test]
+    fn finds_most_widespread_items() {
+        let index = InventoryIndex::new(&parse_rucksacks(include_str!("example.txt")).unwrap());
+
+        let items = index.most_widespread_items();
+
+        assert_eq!(vec![(item('r'), 4), (item('w'), 4), (item('M'), 4), (item('Z'), 4)], items);
+    }
+
+    #[test]
+    fn finds_rucksacks_sharing_at_least_k_items() {
+        let index = InventoryIndex::new(&parse_rucksacks(include_str!("example.txt")).unwrap());
+
+        let pairs = index.pairs_sharing_at_least(6);
+
+        assert_eq!(
+            vec![(0, 3, 6), (0, 5, 6), (1, 3, 8), (1, 5, 8)],
+            pairs
+                .iter()
+                .map(|pair| (pair.first_index, pair.second_index, pair.shared_items.len()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![0, 1],
+            index.rucksacks_sharing_with(3, 6)
+                .iter()
+                .map(|pair| pair.second_index)
+                .collect::<Vec<_>>()
+        );
+    }
+}
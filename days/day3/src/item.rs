@@ -0,0 +1,100 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use crate::{AocPriorities, PriorityScheme};
+
+// Items can be any visible character, which ones count and how much is up to a priority scheme
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Item(char);
+
+impl Item {
+    pub fn new(item: char) -> Result<Self, InvalidItemError> {
+        if item.is_whitespace() || item.is_control() {
+            Err(InvalidItemError(item))
+        } else {
+            Ok(Item(item))
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        self.0
+    }
+
+    fn sort_key(&self) -> (bool, u32) {
+        match AocPriorities.priority(*self) {
+            Some(priority) => (false, priority),
+            None => (true, self.0 as u32)
+        }
+    }
+}
+
+// Letters sort by their puzzle priority and come before everything else, which sorts by code point
+impl Ord for Item {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for Item {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl TryFrom<char> for Item {
+    type Error = InvalidItemError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Item::new(value)
+    }
+}
+
+impl From<Item> for char {
+    fn from(value: Item) -> Self {
+        value.0
+    }
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidItemError(pub char);
+
+impl Display for InvalidItemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} is not a valid item, items must be visible characters", self.0)
+    }
+}
+
+impl Error for InvalidItemError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_unicode_items() {
+        assert_eq!('é', Item::new('é').unwrap().as_char());
+        assert_eq!('1', Item::new('1').unwrap().as_char());
+    }
+
+    #[test]
+    fn rejects_invisible_characters() {
+        assert_eq!(Err(InvalidItemError(' ')), Item::new(' '));
+        assert_eq!(Err(InvalidItemError('\n')), Item::new('\n'));
+    }
+
+    #[test]
+    fn sorts_letters_by_priority_before_other_items() {
+        let mut items: Vec<_> = "é1ZAza".chars().map(|item| Item::new(item).unwrap()).collect();
+
+        items.sort();
+
+        assert_eq!("azAZ1é", items.iter().map(|item| item.as_char()).collect::<String>());
+    }
+}
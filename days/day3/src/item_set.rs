@@ -0,0 +1,276 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{BitAnd, BitOr};
+use std::{slice, vec};
+
+use crate::Item;
+
+// Each ASCII letter gets its own bit in a u64, whatever priority scheme is in use.
+// Anything else is rare enough to live in a sorted list that stays empty, and
+// unallocated, for puzzle inputs.
+#[derive(Default, Clone, PartialEq, Eq, Hash)]
+pub struct ItemSet {
+    letters: u64,
+    others: Vec<Item>
+}
+
+impl ItemSet {
+    pub fn new() -> Self {
+        ItemSet::default()
+    }
+
+    pub fn insert(&mut self, item: Item) {
+        match to_bit(item) {
+            Some(bit) => self.letters |= bit,
+            None => if let Err(i) = self.others.binary_search(&item) {
+                self.others.insert(i, item)
+            }
+        }
+    }
+
+    pub fn remove(&mut self, item: Item) {
+        match to_bit(item) {
+            Some(bit) => self.letters &= !bit,
+            None => if let Ok(i) = self.others.binary_search(&item) {
+                self.others.remove(i);
+            }
+        }
+    }
+
+    pub fn contains(&self, item: Item) -> bool {
+        match to_bit(item) {
+            Some(bit) => self.letters & bit != 0,
+            None => self.others.binary_search(&item).is_ok()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.count_ones() as usize + self.others.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters == 0 && self.others.is_empty()
+    }
+
+    pub fn union(&self, other: &ItemSet) -> ItemSet {
+        let mut others = self.others.clone();
+        for item in &other.others {
+            if let Err(i) = others.binary_search(item) {
+                others.insert(i, *item)
+            }
+        }
+        ItemSet {
+            letters: self.letters | other.letters,
+            others
+        }
+    }
+
+    pub fn intersection(&self, other: &ItemSet) -> ItemSet {
+        ItemSet {
+            letters: self.letters & other.letters,
+            others: self.others
+                .iter()
+                .filter(|item| other.others.binary_search(item).is_ok())
+                .copied()
+                .collect()
+        }
+    }
+
+    pub fn iter(&self) -> ItemSetIter<'_> {
+        ItemSetIter {
+            letters: self.letters,
+            others: self.others.iter()
+        }
+    }
+}
+
+// Lowercase letters take the low 26 bits and uppercase letters the 26 above them
+fn to_bit(item: Item) -> Option<u64> {
+    match item.as_char() {
+        letter @ 'a'..='z' => Some(1 << (letter as u32 - 'a' as u32)),
+        letter @ 'A'..='Z' => Some(1 << (letter as u32 - 'A' as u32 + 26)),
+        _ => None
+    }
+}
+
+fn pop_letter(letters: &mut u64) -> Option<Item> {
+    if *letters == 0 {
+        return None
+    }
+    let index = letters.trailing_zeros() as u8;
+    *letters &= *letters - 1;
+    let letter = if index < 26 { b'a' + index } else { b'A' + index - 26 };
+    Some(Item::new(letter as char).unwrap())
+}
+
+impl Debug for ItemSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(&rhs)
+    }
+}
+
+impl BitAnd for &ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(&rhs)
+    }
+}
+
+impl BitOr for &ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl FromIterator<Item> for ItemSet {
+    fn from_iter<T: IntoIterator<Item = Item>>(iter: T) -> Self {
+        let mut set = ItemSet::new();
+        for item in iter {
+            set.insert(item)
+        }
+        set
+    }
+}
+
+impl<'a> FromIterator<&'a Item> for ItemSet {
+    fn from_iter<T: IntoIterator<Item = &'a Item>>(iter: T) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl IntoIterator for ItemSet {
+    type Item = Item;
+    type IntoIter = ItemSetIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ItemSetIntoIter {
+            letters: self.letters,
+            others: self.others.into_iter()
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a ItemSet {
+    type Item = Item;
+    type IntoIter = ItemSetIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Yields items in their sort order, so lowercase letters come first, then uppercase ones
+pub struct ItemSetIter<'a> {
+    letters: u64,
+    others: slice::Iter<'a, Item>
+}
+
+impl Iterator for ItemSetIter<'_> {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        pop_letter(&mut self.letters).or_else(|| self.others.next().copied())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.letters.count_ones() as usize + self.others.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for ItemSetIter<'_> {}
+
+// Same order as ItemSetIter, but takes the set
+pub struct ItemSetIntoIter {
+    letters: u64,
+    others: vec::IntoIter<Item>
+}
+
+impl Iterator for ItemSetIntoIter {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        pop_letter(&mut self.letters).or_else(|| self.others.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.letters.count_ones() as usize + self.others.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for ItemSetIntoIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intersects_and_unions_item_sets() {
+        let a = to_item_set("vJrwpWtwJgWr");
+        let b = to_item_set("hcsFMMfFFhFp");
+
+        assert_eq!("p", to_string(&a & &b));
+        assert_eq!(a.len() + b.len() - 1, (a | b).len());
+    }
+
+    #[test]
+    fn iterates_items_in_priority_order() {
+        let set = to_item_set("ZaAz");
+
+        assert_eq!("azAZ", to_string(set));
+    }
+
+    #[test]
+    fn iterates_borrowed_sets() {
+        let set = to_item_set("bé🍎Ba");
+
+        let items: String = (&set).into_iter().map(|item| item.as_char()).collect();
+
+        assert_eq!("abBé🍎", items);
+        assert_eq!(5, set.iter().len());
+    }
+
+    #[test]
+    fn holds_items_other_than_letters() {
+        let a = to_item_set("aéb🍎1");
+        let b = to_item_set("🍎cé2");
+
+        assert_eq!(5, a.len());
+        assert!(a.contains(Item::new('é').unwrap()));
+        assert_eq!("é🍎", to_string(&a & &b));
+        assert_eq!("abc12é🍎", to_string(a | b));
+    }
+
+    fn to_item_set(items: &str) -> ItemSet {
+        items
+            .chars()
+            .map(|item| Item::new(item).unwrap())
+            .collect()
+    }
+
+    fn to_string(set: ItemSet) -> String {
+        set
+            .iter()
+            .map(|item| item.as_char())
+            .collect()
+    }
+}
@@ -1,60 +1,73 @@
 // https://adventofcode.com/2022/day/3
 
-use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+mod badges;
+mod group_discovery;
+mod inventory_index;
+mod item;
+mod item_set;
+mod priority_scheme;
+mod repacking;
+
+pub use badges::{find_badges, BadgeError, BadgeReport, GroupBadge, OnBadgeError};
+pub use group_discovery::{discover_badge_groups, BadgeGroup, BadgeGroupDiscovery};
+pub use inventory_index::{InventoryIndex, SharedItems};
+pub use item::{InvalidItemError, Item};
+pub use item_set::{ItemSet, ItemSetIntoIter, ItemSetIter};
+pub use priority_scheme::{AlphabetPriorities, AocPriorities, PriorityScheme, PriorityTable, PriorityTableError, UnscoredItemError};
+pub use repacking::{plan_repacking, Compartment, ItemMove, RepackingPlan, RepackingReport};
 
 pub fn calculate_double_packed_items_priority_sum(rucksacks: Vec<Rucksack>) -> u32 {
+    calculate_double_packed_items_priority_sum_with(rucksacks, &AocPriorities).unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub fn calculate_double_packed_items_priority_sum_with(rucksacks: Vec<Rucksack>, scheme: &impl PriorityScheme) -> Result<u32, UnscoredItemError> {
     rucksacks
         .iter()
-        .map(|rucksack| {
-            let first_compartment_existence_set = rucksack.first_compartment.iter().collect::<HashSet<_>>();
-            let second_compartment_existence_set = rucksack.second_compartment.iter().collect::<HashSet<_>>();
-            first_compartment_existence_set
-                .intersection(&second_compartment_existence_set)
-                .map(|item| get_priority(**item))
-                .sum::<u32>()
-        })
+        .flat_map(|rucksack| rucksack
+            .first_compartment_items()
+            .intersection(&rucksack.second_compartment_items())
+        )
+        .map(|item| priority_scheme::score(scheme, item))
         .sum()
 }
 
 pub fn calculate_priority_sum_of_badges(rucksack_groups: Vec<Vec<Rucksack>>) -> u32 {
+    calculate_priority_sum_of_badges_with(rucksack_groups, &AocPriorities).unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub fn calculate_priority_sum_of_badges_with(rucksack_groups: Vec<Vec<Rucksack>>, scheme: &impl PriorityScheme) -> Result<u32, UnscoredItemError> {
+    let first_rucksack_indices = rucksack_groups.iter().scan(0, |next_index, group| {
+        let first_rucksack_index = *next_index;
+        *next_index += group.len();
+        Some(first_rucksack_index)
+    });
     rucksack_groups
         .iter()
-        .map(|group| {
-            let common_items_set: HashSet<_> = group
-                .iter()
-                .map(|rucksack| rucksack.first_compartment
-                    .iter()
-                    .chain(&rucksack.second_compartment)
-                    .collect::<HashSet<_>>()
-                )
-                .reduce(|acc, curr| acc
-                    .intersection(&curr)
-                    .map(|item| *item)
-                    .collect()
-                )
-                .unwrap();
-            if common_items_set.len() != 1 {
-                panic!("There was a group that didn't have exactly one item in common between all of them. {:?}", group)
-            }
-            let common_item = *common_items_set
-                .into_iter()
-                .map(|item| *item)
-                .collect::<Vec<_>>()
-                .first()
-                .unwrap();
-            get_priority(common_item)
-        })
+        .zip(first_rucksack_indices)
+        .enumerate()
+        .map(|(i, (group, first_rucksack_index))| badges::find_badge(group, i, first_rucksack_index)
+            .unwrap_or_else(|error| panic!("{}. {:?}", error, group))
+        )
+        .map(|badge| priority_scheme::score(scheme, badge))
         .sum()
 }
 
-fn get_priority(char: char) -> u32 {
-    if char.is_ascii_lowercase() {
-        // ascii a starts at 97
-        (char as u32) - 96
-    } else {
-        // ascii A starts at 65
-        (char as u32) - 64 + 26
-    }
+pub fn parse_rucksacks(input: &str) -> Result<Vec<Rucksack>, RucksackError> {
+    parse_rucksacks_with(input, &AocPriorities)
+}
+
+pub fn parse_rucksacks_with(input: &str, scheme: &impl PriorityScheme) -> Result<Vec<Rucksack>, RucksackError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Rucksack::parse_with(line, scheme)
+            .map_err(|error| error.at_line(i + 1))
+        )
+        .collect()
 }
 
 #[cfg(test)]
@@ -88,13 +101,92 @@ mod tests {
         assert_eq!(2581, sum);
     }
 
-    fn parse_input(input: &str) -> Vec<Rucksack> {
-        input
-            .lines()
-            .map(|line| line.into())
+    #[test]
+    #[should_panic(expected = "Group 1 has no item in common, rucksacks [4] are missing 'a'")]
+    fn reports_rucksack_indices_across_groups_without_a_badge() {
+        let rucksacks = parse_input_grouped("zaaz\nyaay\nxaax\nwaaw\nbvvb\nuaau");
+
+        calculate_priority_sum_of_badges(rucksacks);
+    }
+
+    #[test]
+    fn parses_rucksack_into_equal_compartments() {
+        let rucksack: Rucksack = "vJrwpWtwJgWrhcsFMMfFFhFp".parse().unwrap();
+
+        assert_eq!("vJrwpWtwJgWr", to_string(rucksack.first_compartment()));
+        assert_eq!("hcsFMMfFFhFp", to_string(rucksack.second_compartment()));
+    }
+
+    #[test]
+    fn fails_to_parse_rucksack_with_odd_number_of_items() {
+        let result = parse_rucksacks("abab\nabc");
+
+        assert_eq!(Err(RucksackError::OddLength { line: Some(2), length: 3 }), result);
+    }
+
+    #[test]
+    fn fails_to_parse_rucksack_with_invalid_item() {
+        let result = parse_rucksacks("abab\nab1b\nab");
+
+        assert_eq!(Err(RucksackError::InvalidItem { line: Some(2), position: 3, item: '1' }), result);
+    }
+
+    #[test]
+    fn fails_to_build_rucksack_with_unequal_compartments() {
+        let item = Item::new('a').unwrap();
+
+        let result = Rucksack::new(vec![item], vec![item, item]);
+
+        assert_eq!(Err(RucksackError::UnequalCompartments { first_size: 1, second_size: 2 }), result);
+    }
+
+    #[test]
+    fn calculates_priority_sums_with_unicode_scheme() {
+        let greek = AlphabetPriorities::new("αβγδεζηθικλμνξοπρστυφχψω");
+        let rucksacks = parse_rucksacks_with("αβγΣαδεσ\nκλμνΣοπρ\nΣιιτυυωψ", &greek).unwrap();
+
+        let double_packed_sum = calculate_double_packed_items_priority_sum_with(rucksacks.clone(), &greek);
+        let badge_sum = calculate_priority_sum_of_badges_with(vec![rucksacks], &greek);
+
+        assert_eq!(Ok(1), double_packed_sum);
+        assert_eq!(Ok(42), badge_sum);
+    }
+
+    #[test]
+    fn calculates_priority_sum_with_priority_table() {
+        let table: PriorityTable = "p 100\nL 1\nP 0\nv 0\nt 0\ns 0".parse().unwrap();
+        let rucksacks = parse_input(include_str!("example.txt"));
+
+        let sum = calculate_double_packed_items_priority_sum_with(rucksacks, &table);
+
+        assert_eq!(Ok(101), sum);
+    }
+
+    #[test]
+    fn fails_to_sum_items_the_scheme_has_no_priority_for() {
+        let table: PriorityTable = "p 100\nL 1".parse().unwrap();
+        let rucksacks = parse_input(include_str!("example.txt"));
+
+        let sum = calculate_double_packed_items_priority_sum_with(rucksacks, &table);
+
+        assert_eq!(Err(UnscoredItemError(item('P'))), sum);
+    }
+
+    fn to_string(items: &[Item]) -> String {
+        items
+            .iter()
+            .map(|item| item.as_char())
             .collect()
     }
 
+    fn parse_input(input: &str) -> Vec<Rucksack> {
+        parse_rucksacks(input).unwrap()
+    }
+
+    pub(crate) fn item(item: char) -> Item {
+        Item::new(item).unwrap()
+    }
+
     fn parse_input_grouped(input: &str) -> Vec<Vec<Rucksack>> {
         input
             .lines()
@@ -102,41 +194,148 @@ mod tests {
             .chunks_exact(3)
             .map(|chunk| chunk
                 .iter()
-                .map(|line| (*line).into())
+                .map(|line| line.parse().unwrap())
                 .collect()
             )
             .collect()
     }
 }
 
-impl From<&str> for Rucksack {
-    fn from(value: &str) -> Self {
-        let (first_half, second_half) = value.split_at(value.len() / 2);
-        Rucksack::new(
-            first_half.chars().collect(), 
-            second_half.chars().collect()
-        )
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Rucksack {
-    pub first_compartment: Vec<char>,
-    pub second_compartment: Vec<char>
+    first_compartment: Vec<Item>,
+    second_compartment: Vec<Item>
 }
 
 impl Rucksack {
-    pub fn new(first_compartment: Vec<char>, second_compartment: Vec<char>) -> Self {
+    pub fn new(first_compartment: Vec<Item>, second_compartment: Vec<Item>) -> Result<Self, RucksackError> {
         if first_compartment.len() != second_compartment.len() {
-            panic!(
-                "Both compartments must have the same size when making a rucksack. Found first compartment of size {} and second compartment of size {}",
-                first_compartment.len(),
-                second_compartment.len()
-            )
+            return Err(RucksackError::UnequalCompartments {
+                first_size: first_compartment.len(),
+                second_size: second_compartment.len()
+            })
         }
-        Rucksack {
+        Ok(Rucksack {
             first_compartment,
             second_compartment
+        })
+    }
+
+    // Only accepts items the scheme has a priority for
+    pub fn parse_with(value: &str, scheme: &impl PriorityScheme) -> Result<Self, RucksackError> {
+        let items = value
+            .chars()
+            .enumerate()
+            .map(|(i, item)| Item::new(item)
+                .ok()
+                .filter(|item| scheme.priority(*item).is_some())
+                .ok_or(RucksackError::InvalidItem {
+                    line: None,
+                    position: i + 1,
+                    item
+                })
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        if items.len() % 2 != 0 {
+            return Err(RucksackError::OddLength {
+                line: None,
+                length: items.len()
+            })
         }
+        let mut first_compartment = items;
+        let second_compartment = first_compartment.split_off(first_compartment.len() / 2);
+        Rucksack::new(first_compartment, second_compartment)
+    }
+
+    pub fn first_compartment(&self) -> &[Item] {
+        &self.first_compartment
+    }
+
+    pub fn second_compartment(&self) -> &[Item] {
+        &self.second_compartment
+    }
+
+    pub fn first_compartment_items(&self) -> ItemSet {
+        self.first_compartment.iter().collect()
+    }
+
+    pub fn second_compartment_items(&self) -> ItemSet {
+        self.second_compartment.iter().collect()
     }
-}
\ No newline at end of file
+
+    pub fn items(&self) -> ItemSet {
+        self.first_compartment_items() | self.second_compartment_items()
+    }
+}
+
+impl TryFrom<&str> for Rucksack {
+    type Error = RucksackError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Rucksack::parse_with(value, &AocPriorities)
+    }
+}
+
+impl FromStr for Rucksack {
+    type Err = RucksackError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Rucksack::try_from(value)
+    }
+}
+
+impl Display for Rucksack {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for item in self.first_compartment.iter().chain(&self.second_compartment) {
+            write!(f, "{}", item)?
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RucksackError {
+    UnequalCompartments { first_size: usize, second_size: usize },
+    OddLength { line: Option<usize>, length: usize },
+    InvalidItem { line: Option<usize>, position: usize, item: char }
+}
+
+impl RucksackError {
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            RucksackError::OddLength { length, .. } => RucksackError::OddLength { line: Some(line), length },
+            RucksackError::InvalidItem { position, item, .. } => RucksackError::InvalidItem { line: Some(line), position, item },
+            error => error
+        }
+    }
+}
+
+impl Display for RucksackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RucksackError::UnequalCompartments { first_size, second_size } => write!(
+                f,
+                "Both compartments must have the same size when making a rucksack. Found first compartment of size {} and second compartment of size {}",
+                first_size,
+                second_size
+            ),
+            RucksackError::OddLength { line, length } => {
+                write_line(f, line)?;
+                write!(f, "Rucksack has {} items which cannot be split into two equal compartments", length)
+            },
+            RucksackError::InvalidItem { line, position, item } => {
+                write_line(f, line)?;
+                write!(f, "Invalid item {:?} at position {}, it has no priority", item, position)
+            }
+        }
+    }
+}
+
+fn write_line(f: &mut Formatter<'_>, line: &Option<usize>) -> fmt::Result {
+    match line {
+        Some(line) => write!(f, "Line {}: ", line),
+        None => Ok(())
+    }
+}
+
+impl Error for RucksackError {}
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::Item;
+
+pub trait PriorityScheme {
+    // None means the item isn't known to the scheme
+    fn priority(&self, item: Item) -> Option<u32>;
+}
+
+impl<T: PriorityScheme + ?Sized> PriorityScheme for &T {
+    fn priority(&self, item: Item) -> Option<u32> {
+        (**self).priority(item)
+    }
+}
+
+pub(crate) fn score(scheme: &impl PriorityScheme, item: Item) -> Result<u32, UnscoredItemError> {
+    scheme.priority(item).ok_or(UnscoredItemError(item))
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UnscoredItemError(pub Item);
+
+impl Display for UnscoredItemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' has no priority in this scheme", self.0)
+    }
+}
+
+impl Error for UnscoredItemError {}
+
+// The puzzle's scheme of a through z scoring 1 through 26 and A through Z scoring 27 through 52
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct AocPriorities;
+
+impl PriorityScheme for AocPriorities {
+    fn priority(&self, item: Item) -> Option<u32> {
+        let char = item.as_char();
+        if char.is_ascii_lowercase() {
+            // ascii a starts at 97
+            Some((char as u32) - 96)
+        } else if char.is_ascii_uppercase() {
+            // ascii A starts at 65
+            Some((char as u32) - 64 + 26)
+        } else {
+            None
+        }
+    }
+}
+
+// Generalizes the puzzle's scheme to any alphabet. The lowercase letters score by their
+// position in the alphabet and their uppercase forms, found through Unicode case mapping,
+// score after all of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlphabetPriorities {
+    alphabet: Vec<char>
+}
+
+impl AlphabetPriorities {
+    pub fn new(alphabet: &str) -> Self {
+        AlphabetPriorities {
+            alphabet: alphabet.chars().collect()
+        }
+    }
+}
+
+impl PriorityScheme for AlphabetPriorities {
+    fn priority(&self, item: Item) -> Option<u32> {
+        let char = item.as_char();
+        let position = |char: char| self.alphabet
+            .iter()
+            .position(|letter| *letter == char)
+            .map(|i| i as u32 + 1);
+        if let Some(priority) = position(char) {
+            return Some(priority)
+        }
+        if !char.is_uppercase() {
+            return None
+        }
+        let mut lowercase = char.to_lowercase();
+        match (lowercase.next(), lowercase.next()) {
+            (Some(lowercase), None) => position(lowercase).map(|priority| priority + self.alphabet.len() as u32),
+            _ => None
+        }
+    }
+}
+
+// Reads lines of an item followed by its priority, skipping blank lines and lines starting with #
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PriorityTable {
+    priorities: HashMap<Item, u32>
+}
+
+impl PriorityTable {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PriorityTableError> {
+        fs::read_to_string(path)
+            .map_err(PriorityTableError::Io)?
+            .parse()
+    }
+
+    pub fn insert(&mut self, item: Item, priority: u32) -> Option<u32> {
+        self.priorities.insert(item, priority)
+    }
+}
+
+impl PriorityScheme for PriorityTable {
+    fn priority(&self, item: Item) -> Option<u32> {
+        self.priorities.get(&item).copied()
+    }
+}
+
+impl FromStr for PriorityTable {
+    type Err = PriorityTableError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut table = PriorityTable::default();
+        for (i, line) in value.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+            let invalid_line = || PriorityTableError::InvalidLine {
+                line: line_number,
+                content: line.to_string()
+            };
+            let (item, priority) = line.split_once(char::is_whitespace).ok_or_else(invalid_line)?;
+            let mut chars = item.chars();
+            let item = match (chars.next(), chars.next()) {
+                (Some(item), None) => Item::new(item).map_err(|_| invalid_line())?,
+                _ => return Err(invalid_line())
+            };
+            let priority = priority.trim().parse().map_err(|_| invalid_line())?;
+            if table.insert(item, priority).is_some() {
+                return Err(PriorityTableError::DuplicateItem { line: line_number, item })
+            }
+        }
+        Ok(table)
+    }
+}
+
+#[derive(Debug)]
+pub enum PriorityTableError {
+    Io(io::Error),
+    InvalidLine { line: usize, content: String },
+    DuplicateItem { line: usize, item: Item }
+}
+
+impl Display for PriorityTableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PriorityTableError::Io(error) => write!(f, "Could not read priority table: {}", error),
+            PriorityTableError::InvalidLine { line, content } => write!(f, "Line {}: expected an item and a priority but found '{}'", line, content),
+            PriorityTableError::DuplicateItem { line, item } => write!(f, "Line {}: '{}' was already given a priority", line, item)
+        }
+    }
+}
+
+impl Error for PriorityTableError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PriorityTableError::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::item;
+
+    #[test]
+    fn scores_letters_with_aoc_priorities() {
+        assert_eq!(Some(1), AocPriorities.priority(item('a')));
+        assert_eq!(Some(26), AocPriorities.priority(item('z')));
+        assert_eq!(Some(27), AocPriorities.priority(item('A')));
+        assert_eq!(Some(52), AocPriorities.priority(item('Z')));
+        assert_eq!(None, AocPriorities.priority(item('1')));
+        assert_eq!(None, AocPriorities.priority(item('é')));
+    }
+
+    #[test]
+    fn scores_unicode_alphabets() {
+        let greek = AlphabetPriorities::new("αβγδεζηθικλμνξοπρστυφχψω");
+
+        assert_eq!(Some(3), greek.priority(item('γ')));
+        assert_eq!(Some(42), greek.priority(item('Σ')));
+        assert_eq!(None, greek.priority(item('a')));
+    }
+
+    #[test]
+    fn loads_priority_table() {
+        let table: PriorityTable = "# snacks\n🍎 10\n\nx 2".parse().unwrap();
+
+        assert_eq!(Some(10), table.priority(item('🍎')));
+        assert_eq!(Some(2), table.priority(item('x')));
+        assert_eq!(None, table.priority(item('y')));
+    }
+
+    #[test]
+    fn fails_to_load_malformed_priority_table() {
+        assert!(matches!(
+            "a 1\nb one".parse::<PriorityTable>(),
+            Err(PriorityTableError::InvalidLine { line: 2, .. })
+        ));
+        assert!(matches!(
+            "a 1\na 2".parse::<PriorityTable>(),
+            Err(PriorityTableError::DuplicateItem { line: 2, .. })
+        ));
+        assert!(matches!(
+            PriorityTable::load("does/not/exist.txt"),
+            Err(PriorityTableError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{Item, Rucksack};
+
+pub fn plan_repacking(rucksacks: &[Rucksack]) -> RepackingReport {
+    let mut report = RepackingReport::default();
+    for (rucksack_index, rucksack) in rucksacks.iter().enumerate() {
+        match plan_rucksack_repacking(rucksack) {
+            Some((moves, repacked)) => report.plans.push(RepackingPlan {
+                rucksack_index,
+                moves,
+                repacked
+            }),
+            None => report.impossible_rucksacks.push(rucksack_index)
+        }
+    }
+    report
+}
+
+// Every item type has to end up wholly in one compartment. Choosing which types go
+// in the first compartment is a subset sum over the type counts that must add up to
+// the compartment size, so a knapsack over that size finds the cheapest choice.
+fn plan_rucksack_repacking(rucksack: &Rucksack) -> Option<(Vec<ItemMove>, Rucksack)> {
+    let compartment_size = rucksack.first_compartment().len();
+    let item_counts = count_items(rucksack);
+
+    // cheapest[i][size] is the fewest moves to fill the first compartment up to size using the first i item types,
+    // along with whether the last of those types was kept in the first compartment
+    let mut cheapest: Vec<Vec<Option<(usize, bool)>>> = vec![vec![None; compartment_size + 1]; item_counts.len() + 1];
+    cheapest[0][0] = Some((0, false));
+    for (i, (_, first_count, second_count)) in item_counts.iter().enumerate() {
+        for size in 0..=compartment_size {
+            let Some((cost, _)) = cheapest[i][size] else {
+                continue
+            };
+            relax(&mut cheapest[i + 1][size], cost + first_count, false);
+            let size_with_item = size + first_count + second_count;
+            if size_with_item <= compartment_size {
+                relax(&mut cheapest[i + 1][size_with_item], cost + second_count, true);
+            }
+        }
+    }
+    cheapest[item_counts.len()][compartment_size]?;
+
+    let mut in_first_compartment = vec![false; item_counts.len()];
+    let mut size = compartment_size;
+    for (i, (_, first_count, second_count)) in item_counts.iter().enumerate().rev() {
+        let (_, in_first) = cheapest[i + 1][size].unwrap();
+        if in_first {
+            in_first_compartment[i] = true;
+            size -= first_count + second_count
+        }
+    }
+
+    let mut moves = Vec::new();
+    for ((item, first_count, second_count), in_first) in item_counts.iter().zip(&in_first_compartment) {
+        match (*in_first, *first_count, *second_count) {
+            (true, _, count) if count > 0 => moves.push(ItemMove { item: *item, from: Compartment::Second, count }),
+            (false, count, _) if count > 0 => moves.push(ItemMove { item: *item, from: Compartment::First, count }),
+            _ => {}
+        }
+    }
+
+    let in_first = |item: &Item| item_counts
+        .iter()
+        .zip(&in_first_compartment)
+        .any(|((other, _, _), in_first)| other == item && *in_first);
+    let all_items = rucksack.first_compartment().iter().chain(rucksack.second_compartment());
+    let repacked = Rucksack::new(
+        all_items.clone().filter(|item| in_first(item)).copied().collect(),
+        all_items.filter(|item| !in_first(item)).copied().collect()
+    ).unwrap();
+    Some((moves, repacked))
+}
+
+fn relax(cell: &mut Option<(usize, bool)>, cost: usize, in_first: bool) {
+    if cell.is_none_or(|(best_cost, _)| cost < best_cost) {
+        *cell = Some((cost, in_first))
+    }
+}
+
+fn count_items(rucksack: &Rucksack) -> Vec<(Item, usize, usize)> {
+    rucksack
+        .items()
+        .iter()
+        .map(|item| (
+            item,
+            rucksack.first_compartment().iter().filter(|other| **other == item).count(),
+            rucksack.second_compartment().iter().filter(|other| **other == item).count()
+        ))
+        .collect()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Compartment {
+    First,
+    Second
+}
+
+// Moves always go to the other compartment
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ItemMove {
+    pub item: Item,
+    pub from: Compartment,
+    pub count: usize
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepackingPlan {
+    pub rucksack_index: usize,
+    pub moves: Vec<ItemMove>,
+    pub repacked: Rucksack
+}
+
+impl RepackingPlan {
+    pub fn num_moved_items(&self) -> usize {
+        self.moves
+            .iter()
+            .map(|item_move| item_move.count)
+            .sum()
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RepackingReport {
+    pub plans: Vec<RepackingPlan>,
+    pub impossible_rucksacks: Vec<usize>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_rucksacks;
+
+    #[test]
+    fn plans_fewest_moves_for_example_input() {
+        let rucksacks = parse_rucksacks(include_str!("example.txt")).unwrap();
+
+        let report = plan_repacking(&rucksacks);
+
+        assert!(report.impossible_rucksacks.is_empty());
+        assert_eq!(vec![2, 4, 2, 4, 4, 2], report.plans.iter().map(|plan| plan.num_moved_items()).collect::<Vec<_>>());
+        for plan in report.plans {
+            assert!(plan.repacked.first_compartment_items().intersection(&plan.repacked.second_compartment_items()).is_empty());
+        }
+    }
+
+    #[test]
+    fn leaves_sorted_rucksacks_alone() {
+        let rucksacks = parse_rucksacks("aabc").unwrap();
+
+        let report = plan_repacking(&rucksacks);
+
+        assert_eq!(Vec::<ItemMove>::new(), report.plans[0].moves);
+        assert_eq!(rucksacks[0], report.plans[0].repacked);
+    }
+
+    #[test]
+    fn swaps_items_to_keep_compartments_equal() {
+        let rucksacks = parse_rucksacks("abccaddd").unwrap();
+
+        let report = plan_repacking(&rucksacks);
+
+        assert_eq!(
+            vec![
+                ItemMove { item: Item::new('a').unwrap(), from: Compartment::Second, count: 1 },
+                ItemMove { item: Item::new('b').unwrap(), from: Compartment::First, count: 1 }
+            ],
+            report.plans[0].moves
+        );
+        assert_eq!("accabddd", report.plans[0].repacked.to_string());
+    }
+
+    #[test]
+    fn reports_rucksacks_that_cannot_be_repacked() {
+        let rucksacks = parse_rucksacks("abab\naaab").unwrap();
+
+        let report = plan_repacking(&rucksacks);
+
+        assert_eq!(vec![1], report.impossible_rucksacks);
+        assert_eq!(0, report.plans[0].rucksack_index);
+    }
+}
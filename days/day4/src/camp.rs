@@ -0,0 +1,202 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::{AssignmentGroup, AssignmentPair, Interval, IntervalSet};
+
+// Every elf's assignment across the whole camp rather than one pair at a time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Camp {
+    assignments: Vec<(ElfId, Interval<u32>)>
+}
+
+impl Camp {
+    pub fn new(assignment_pairs: &[AssignmentPair]) -> Self {
+        Camp {
+            assignments: assignment_pairs
+                .iter()
+                .enumerate()
+                .flat_map(|(pair_index, pair)| [
+                    (ElfId { pair_index, member: 0 }, pair.first()),
+                    (ElfId { pair_index, member: 1 }, pair.second())
+                ])
+                .collect()
+        }
+    }
+
+    pub fn from_groups(assignment_groups: &[AssignmentGroup]) -> Self {
+        Camp {
+            assignments: assignment_groups
+                .iter()
+                .enumerate()
+                .flat_map(|(pair_index, group)| group
+                    .assignments()
+                    .iter()
+                    .enumerate()
+                    .map(move |(member, assignment)| (ElfId { pair_index, member }, *assignment))
+                )
+                .collect()
+        }
+    }
+
+    pub fn assignments(&self) -> &[(ElfId, Interval<u32>)] {
+        &self.assignments
+    }
+
+    // Sweeps assignments by start while keeping the ones still running in a heap keyed by
+    // end, so each elf is compared only against elves it actually overlaps. That makes it
+    // O(n log n + k) for k overlaps.
+    pub fn find_overlaps(&self) -> Vec<ElfOverlap> {
+        let mut by_start: Vec<_> = (0..self.assignments.len()).collect();
+        by_start.sort_by_key(|i| (self.assignments[*i].1.start(), *i));
+
+        let mut overlaps = Vec::new();
+        let mut running = BinaryHeap::new();
+        for i in by_start {
+            let (elf, assignment) = self.assignments[i];
+            while running
+                .peek()
+                .is_some_and(|Reverse((end, _))| *end < assignment.start())
+            {
+                running.pop();
+            }
+            for Reverse((_, other)) in running.iter() {
+                let (other_elf, other_assignment): (ElfId, Interval<u32>) = self.assignments[*other];
+                let (first, second) = if other_elf < elf { (other_elf, elf) } else { (elf, other_elf) };
+                overlaps.push(ElfOverlap {
+                    first,
+                    second,
+                    overlap: assignment.intersection(&other_assignment).unwrap()
+                })
+            }
+            running.push(Reverse((assignment.end(), i)))
+        }
+        overlaps.sort_by_key(|overlap| (overlap.first, overlap.second));
+        overlaps
+    }
+
+    // The most elves assigned to any one section, along with every section assigned that many
+    pub fn max_coverage(&self) -> Option<(usize, IntervalSet<u32>)> {
+        // Coverage ends just after an assignment's inclusive end, which is u32::MAX + 1 at worst
+        let mut events: Vec<(u64, isize)> = self.assignments
+            .iter()
+            .flat_map(|(_, assignment)| [
+                (assignment.start() as u64, 1),
+                (assignment.end() as u64 + 1, -1)
+            ])
+            .collect();
+        events.sort();
+
+        let mut depth = 0;
+        let mut max_depth = 0;
+        let mut deepest = Vec::new();
+        for (i, (position, change)) in events.iter().enumerate() {
+            depth += change;
+            let next_position = match events.get(i + 1) {
+                Some((next_position, _)) if next_position > position => *next_position,
+                _ => continue
+            };
+            let section = Interval::inclusive(*position as u32, (next_position - 1) as u32).unwrap();
+            if depth as usize > max_depth {
+                max_depth = depth as usize;
+                deepest.clear()
+            }
+            if depth as usize == max_depth && depth > 0 {
+                deepest.push(section)
+            }
+        }
+        if max_depth == 0 {
+            return None
+        }
+        Some((max_depth, deepest.into_iter().collect()))
+    }
+
+    pub fn covered_sections(&self) -> IntervalSet<u32> {
+        self.assignments
+            .iter()
+            .map(|(_, assignment)| *assignment)
+            .collect()
+    }
+
+    pub fn uncovered_sections(&self, within: Interval<u32>) -> IntervalSet<u32> {
+        self.covered_sections().complement(within)
+    }
+}
+
+// Member is the elf's position within its line of the input
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ElfId {
+    pub pair_index: usize,
+    pub member: usize
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ElfOverlap {
+    pub first: ElfId,
+    pub second: ElfId,
+    pub overlap: Interval<u32>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_assignment_groups, parse_assignment_pairs};
+
+    #[test]
+    fn finds_same_overlaps_as_comparing_every_pair_of_elves() {
+        let camp = Camp::new(&parse_input(include_str!("actual.txt")));
+
+        let overlaps = camp.find_overlaps();
+
+        let mut expected = Vec::new();
+        for (i, (first, first_assignment)) in camp.assignments().iter().enumerate() {
+            for (second, second_assignment) in &camp.assignments()[i + 1..] {
+                if let Some(overlap) = first_assignment.intersection(second_assignment) {
+                    expected.push(ElfOverlap { first: *first, second: *second, overlap })
+                }
+            }
+        }
+        assert_eq!(expected, overlaps);
+    }
+
+    #[test]
+    fn finds_max_coverage_in_example_input() {
+        let camp = Camp::new(&parse_input(include_str!("example.txt")));
+
+        let (depth, sections) = camp.max_coverage().unwrap();
+
+        assert_eq!(8, depth);
+        assert_eq!("[6, 6]", sections.to_string());
+    }
+
+    #[test]
+    fn finds_overlaps_across_groups() {
+        let camp = Camp::from_groups(&parse_assignment_groups("1-2,3-4,5-6\n4-5").unwrap());
+
+        let overlaps = camp.find_overlaps();
+
+        assert_eq!(
+            vec![
+                (ElfId { pair_index: 0, member: 1 }, ElfId { pair_index: 1, member: 0 }, Interval::point(4)),
+                (ElfId { pair_index: 0, member: 2 }, ElfId { pair_index: 1, member: 0 }, Interval::point(5))
+            ],
+            overlaps
+                .iter()
+                .map(|overlap| (overlap.first, overlap.second, overlap.overlap))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn finds_uncovered_gaps() {
+        let camp = Camp::new(&parse_input("2-3,8-9\n5-5,9-12"));
+
+        let gaps = camp.uncovered_sections(Interval::inclusive(1, 15).unwrap());
+
+        assert_eq!("[1, 1] ∪ [4, 4] ∪ [6, 7] ∪ [13, 15]", gaps.to_string());
+        assert_eq!(None, Camp::new(&[]).max_coverage());
+    }
+
+    fn parse_input(input: &str) -> Vec<AssignmentPair> {
+        parse_assignment_pairs(input).unwrap()
+    }
+}
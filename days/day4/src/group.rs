@@ -0,0 +1,155 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{parse_assignments, AssignmentPair, Interval, IntervalSet, ParseAssignmentError};
+
+pub fn count_overlapping_groups(assignment_groups: &[AssignmentGroup]) -> u32 {
+    assignment_groups
+        .iter()
+        .filter(|group| group.has_overlap())
+        .count() as u32
+}
+
+pub fn count_fully_containing_groups(assignment_groups: &[AssignmentGroup]) -> u32 {
+    assignment_groups
+        .iter()
+        .filter(|group| group.has_full_containment())
+        .count() as u32
+}
+
+// Any number of elves sharing a line of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssignmentGroup(Vec<Interval<u32>>);
+
+impl AssignmentGroup {
+    pub fn new(assignments: Vec<Interval<u32>>) -> Self {
+        AssignmentGroup(assignments)
+    }
+
+    pub fn assignments(&self) -> &[Interval<u32>] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn sections(&self) -> IntervalSet<u32> {
+        self.0.iter().copied().collect()
+    }
+
+    pub fn overlapping_members(&self) -> Vec<(usize, usize)> {
+        let mut overlapping = Vec::new();
+        for (i, a) in self.0.iter().enumerate() {
+            for (j, b) in self.0.iter().enumerate().skip(i + 1) {
+                if a.overlaps(b) {
+                    overlapping.push((i, j))
+                }
+            }
+        }
+        overlapping
+    }
+
+    // Once sorted by start, any overlap shows up between neighbours
+    pub fn has_overlap(&self) -> bool {
+        let mut assignments = self.0.clone();
+        assignments.sort_by_key(|assignment| assignment.start());
+        assignments
+            .windows(2)
+            .any(|window| window[0].overlaps(&window[1]))
+    }
+
+    // Sorted by start and then longest first, an assignment is contained by another exactly
+    // when an earlier one reaches at least as far
+    pub fn has_full_containment(&self) -> bool {
+        let mut assignments = self.0.clone();
+        assignments.sort_by(|a, b| a.start().cmp(&b.start()).then(b.end().cmp(&a.end())));
+        let mut furthest_end = None;
+        for assignment in assignments {
+            if furthest_end.is_some_and(|end| end >= assignment.end()) {
+                return true
+            }
+            furthest_end = furthest_end.max(Some(assignment.end()))
+        }
+        false
+    }
+}
+
+impl Display for AssignmentGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, assignment) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?
+            }
+            write!(f, "{}-{}", assignment.start(), assignment.end())?
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for AssignmentGroup {
+    type Err = ParseAssignmentError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_assignments(value).map(AssignmentGroup)
+    }
+}
+
+impl From<AssignmentPair> for AssignmentGroup {
+    fn from(value: AssignmentPair) -> Self {
+        AssignmentGroup(vec![value.first(), value.second()])
+    }
+}
+
+impl TryFrom<AssignmentGroup> for AssignmentPair {
+    type Error = ParseAssignmentError;
+
+    fn try_from(value: AssignmentGroup) -> Result<Self, Self::Error> {
+        match value.0.as_slice() {
+            [first, second] => Ok(AssignmentPair::new(*first, *second)),
+            assignments => Err(ParseAssignmentError::WrongArity {
+                line: None,
+                expected: 2,
+                found: assignments.len()
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_assignment_groups;
+
+    #[test]
+    fn counts_overlaps_in_groups_of_any_size() {
+        let groups = parse_assignment_groups("1-2,3-4,5-6\n1-3,5-6,3-3\n2-8\n1-9,4-4,12-20,15-16").unwrap();
+
+        assert_eq!(2, count_overlapping_groups(&groups));
+        assert_eq!(2, count_fully_containing_groups(&groups));
+        assert_eq!(vec![(0, 2)], groups[1].overlapping_members());
+    }
+
+    #[test]
+    fn counts_same_overlaps_as_pairs_for_example_input() {
+        let groups = parse_assignment_groups(include_str!("example.txt")).unwrap();
+
+        assert_eq!(4, count_overlapping_groups(&groups));
+        assert_eq!(2, count_fully_containing_groups(&groups));
+    }
+
+    #[test]
+    fn converts_between_pairs_and_groups() {
+        let group: AssignmentGroup = "2-4,6-8,1-1".parse().unwrap();
+
+        assert_eq!("2-4,6-8,1-1", group.to_string());
+        assert_eq!(
+            Err(ParseAssignmentError::WrongArity { line: None, expected: 2, found: 3 }),
+            AssignmentPair::try_from(group)
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter::Sum;
+use std::ops::Add;
+use std::str::FromStr;
+
+// Integer widths an interval can be made of, each paired with a type wide enough to count every value in its range
+pub trait Integer: Copy + Ord + Debug + Display + FromStr {
+    type Length: Copy + Ord + Debug + Display + Default + Add<Output = Self::Length> + Sum;
+
+    fn successor(self) -> Option<Self>;
+    fn predecessor(self) -> Option<Self>;
+    fn span(start: Self, end: Self) -> Self::Length;
+    fn min_value() -> Self;
+    fn max_value() -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($integer:ty => $length:ty),*) => {
+        $(
+            impl Integer for $integer {
+                type Length = $length;
+
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn predecessor(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn span(start: Self, end: Self) -> Self::Length {
+                    (end as i128 - start as i128 + 1) as $length
+                }
+
+                fn min_value() -> Self {
+                    <$integer>::MIN
+                }
+
+                fn max_value() -> Self {
+                    <$integer>::MAX
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(
+    u8 => u16, u16 => u32, u32 => u64, u64 => u128, usize => u128,
+    i8 => u16, i16 => u32, i32 => u64, i64 => u128, isize => u128
+);
+
+// A non empty run of integers. Bounds are stored inclusively so an interval can reach the
+// largest value of its type, which a half open std::ops::Range can't.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    start: T,
+    end: T
+}
+
+impl<T: Integer> Interval<T> {
+    pub fn inclusive(start: T, end: T) -> Option<Self> {
+        if start <= end {
+            Some(Interval { start, end })
+        } else {
+            None
+        }
+    }
+
+    pub fn exclusive(start: T, end: T) -> Option<Self> {
+        Interval::inclusive(start, end.predecessor()?)
+    }
+
+    pub fn point(value: T) -> Self {
+        Interval { start: value, end: value }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    // Inclusive
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    pub fn len(&self) -> T::Length {
+        T::span(self.start, self.end)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        Interval::inclusive(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    // True when the two can be merged into one interval without gaps
+    fn touches(&self, other: &Interval<T>) -> bool {
+        self.overlaps(other)
+            || self.end.successor() == Some(other.start)
+            || other.end.successor() == Some(self.start)
+    }
+}
+
+impl<T: Integer> Debug for Interval<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+// Writes [start, end] or, with the alternate flag, [start, end) when the end fits in the type
+impl<T: Integer> Display for Interval<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (f.alternate(), self.end.successor()) {
+            (true, Some(exclusive_end)) => write!(f, "[{}, {})", self.start, exclusive_end),
+            _ => write!(f, "[{}, {}]", self.start, self.end)
+        }
+    }
+}
+
+// Reads interval notation like [2, 4], [2, 5), (1, 4] or (1, 5)
+impl<T: Integer> FromStr for Interval<T> {
+    type Err = ParseIntervalError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid_format = || ParseIntervalError::InvalidFormat(value.to_string());
+        let value = value.trim();
+        let mut chars = value.chars();
+        let (opening, closing) = (chars.next().ok_or_else(invalid_format)?, chars.next_back().ok_or_else(invalid_format)?);
+        let (start, end) = chars.as_str().split_once(',').ok_or_else(invalid_format)?;
+        let parse_bound = |bound: &str| bound
+            .trim()
+            .parse::<T>()
+            .map_err(|_| ParseIntervalError::InvalidBound(bound.trim().to_string()));
+        let (start, end) = (parse_bound(start)?, parse_bound(end)?);
+        let start = match opening {
+            '[' => Some(start),
+            '(' => start.successor(),
+            _ => return Err(invalid_format())
+        };
+        let end = match closing {
+            ']' => Some(end),
+            ')' => end.predecessor(),
+            _ => return Err(invalid_format())
+        };
+        start
+            .zip(end)
+            .and_then(|(start, end)| Interval::inclusive(start, end))
+            .ok_or(ParseIntervalError::Empty)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseIntervalError {
+    InvalidFormat(String),
+    InvalidBound(String),
+    Empty
+}
+
+impl Display for ParseIntervalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseIntervalError::InvalidFormat(value) => write!(f, "Expected interval notation like [2, 4] or [2, 5) but found '{}'", value),
+            ParseIntervalError::InvalidBound(value) => write!(f, "'{}' is not a valid bound", value),
+            ParseIntervalError::Empty => write!(f, "Intervals can't be empty")
+        }
+    }
+}
+
+impl Error for ParseIntervalError {}
+
+// Kept as sorted intervals that neither overlap nor touch, so equal sets always compare equal
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { intervals: Vec::new() }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let first_touching = self.intervals.partition_point(|other| other.end < interval.start && !other.touches(&interval));
+        let mut merged = interval;
+        let mut last_touching = first_touching;
+        while last_touching < self.intervals.len() && self.intervals[last_touching].touches(&merged) {
+            let other = self.intervals[last_touching];
+            merged = Interval {
+                start: merged.start.min(other.start),
+                end: merged.end.max(other.end)
+            };
+            last_touching += 1
+        }
+        self.intervals.splice(first_touching..last_touching, [merged]);
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn total_len(&self) -> T::Length {
+        self.intervals.iter().map(|interval| interval.len()).sum()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|interval| interval.end < value);
+        self.intervals.get(i).is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut union = self.clone();
+        for interval in &other.intervals {
+            union.insert(*interval)
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(intersection) = a.intersection(&b) {
+                intervals.push(intersection)
+            }
+            if a.end < b.end {
+                i += 1
+            } else {
+                j += 1
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        match self.bounds() {
+            Some(bounds) => self.intersection(&other.complement(bounds)),
+            None => IntervalSet::new()
+        }
+    }
+
+    // Everything within the bound that isn't in the set
+    pub fn complement(&self, within: Interval<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let mut next_start = Some(within.start);
+        for interval in &self.intervals {
+            let Some(start) = next_start else {
+                break
+            };
+            if interval.end < start {
+                continue
+            }
+            if let Some(gap) = interval.start
+                .predecessor()
+                .and_then(|end| Interval::inclusive(start, end.min(within.end)))
+            {
+                intervals.push(gap)
+            }
+            next_start = interval.end.successor()
+        }
+        if let Some(gap) = next_start.and_then(|start| Interval::inclusive(start, within.end)) {
+            intervals.push(gap)
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn bounds(&self) -> Option<Interval<T>> {
+        Some(Interval {
+            start: self.intervals.first()?.start,
+            end: self.intervals.last()?.end
+        })
+    }
+}
+
+impl<T: Integer> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet::new()
+    }
+}
+
+impl<T: Integer> From<Interval<T>> for IntervalSet<T> {
+    fn from(value: Interval<T>) -> Self {
+        IntervalSet { intervals: vec![value] }
+    }
+}
+
+impl<T: Integer> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval)
+        }
+        set
+    }
+}
+
+impl<T: Integer> Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl<T: Integer> Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.intervals.is_empty() {
+            return write!(f, "∅")
+        }
+        for (i, interval) in self.intervals.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ∪ ")?
+            }
+            Display::fmt(interval, f)?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_inclusive_and_exclusive_notation() {
+        assert_eq!(Interval::inclusive(2, 4), "[2, 4]".parse().ok());
+        assert_eq!(Interval::inclusive(2, 4), "[2, 5)".parse().ok());
+        assert_eq!(Interval::inclusive(2, 4), "(1, 4]".parse().ok());
+        assert_eq!(Interval::inclusive(2_u8, 4), "(1, 5)".parse().ok());
+        assert_eq!(Err(ParseIntervalError::Empty), "[4, 4)".parse::<Interval<u32>>());
+        assert_eq!(Err(ParseIntervalError::InvalidBound("-1".to_string())), "[-1, 4]".parse::<Interval<u32>>());
+        assert_eq!(Err(ParseIntervalError::InvalidFormat("2-4".to_string())), "2-4".parse::<Interval<u32>>());
+    }
+
+    #[test]
+    fn displays_interval_notation() {
+        let interval = Interval::inclusive(2_i64, 4).unwrap();
+
+        assert_eq!("[2, 4]", interval.to_string());
+        assert_eq!("[2, 5)", format!("{:#}", interval));
+        assert_eq!("[0, 255]", format!("{:#}", Interval::inclusive(0_u8, 255).unwrap()));
+    }
+
+    #[test]
+    fn measures_intervals_reaching_the_edges_of_their_type() {
+        assert_eq!(256_u16, Interval::inclusive(u8::MIN, u8::MAX).unwrap().len());
+        assert_eq!(1_u128 << 64, Interval::inclusive(i64::MIN, i64::MAX).unwrap().len());
+    }
+
+    #[test]
+    fn merges_overlapping_and_touching_intervals() {
+        let set: IntervalSet<u32> = [interval(6, 8), interval(2, 3), interval(4, 4), interval(10, 12), interval(7, 11)]
+            .into_iter()
+            .collect();
+
+        assert_eq!("[2, 4] ∪ [6, 12]", set.to_string());
+        assert_eq!(10, set.total_len());
+        assert!(set.contains(9));
+        assert!(!set.contains(5));
+    }
+
+    #[test]
+    fn combines_interval_sets() {
+        let a: IntervalSet<i32> = [interval(-5, 0), interval(4, 9)].into_iter().collect();
+        let b: IntervalSet<i32> = [interval(-2, 5), interval(8, 20)].into_iter().collect();
+
+        assert_eq!("[-5, 20]", a.union(&b).to_string());
+        assert_eq!("[-2, 0] ∪ [4, 5] ∪ [8, 9]", a.intersection(&b).to_string());
+        assert_eq!("[-5, -3] ∪ [6, 7]", a.difference(&b).to_string());
+        assert_eq!("[-10, -6] ∪ [1, 3] ∪ [10, 10]", a.complement(interval(-10, 10)).to_string());
+        assert_eq!("∅", a.difference(&a).to_string());
+    }
+
+    #[test]
+    fn complements_up_to_the_edges_of_the_type() {
+        let set: IntervalSet<u8> = [interval(0, 9), interval(250, 255)].into_iter().collect();
+
+        let complement = set.complement(interval(0, 255));
+
+        assert_eq!("[10, 249]", complement.to_string());
+    }
+
+    fn interval<T: Integer>(start: T, end: T) -> Interval<T> {
+        Interval::inclusive(start, end).unwrap()
+    }
+}
@@ -1,12 +1,28 @@
 // https://adventofcode.com/2022/day/4
 
-use std::ops::Range;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+mod camp;
+mod group;
+mod interval;
+mod overlap;
+mod reassignment;
+mod render;
+
+pub use camp::{Camp, ElfId, ElfOverlap};
+pub use group::{count_fully_containing_groups, count_overlapping_groups, AssignmentGroup};
+pub use interval::{Integer, Interval, IntervalSet, ParseIntervalError};
+pub use overlap::{measure_overlaps, OverlapSummary, PairOverlap};
+pub use reassignment::{reassign_camp, reassign_within_pairs, Reassignment, ReassignmentError};
+pub use render::render_assignment_pairs;
 
 pub fn count_fully_containing_overlaps(assignment_pairs: Vec<AssignmentPair>) -> u32 {
     assignment_pairs
         .iter()
         .map(|pair| {
-            if contains(&pair.0, &pair.1) || contains(&pair.1, &pair.0) {
+            if pair.0.contains_interval(&pair.1) || pair.1.contains_interval(&pair.0) {
                 1
             } else {
                 0
@@ -19,7 +35,7 @@ pub fn count_overlaps(assignment_pairs: Vec<AssignmentPair>) -> u32 {
     assignment_pairs
         .iter()
         .map(|pair| {
-            if overlaps(&pair.0, &pair.1) {
+            if pair.0.overlaps(&pair.1) {
                 1
             } else {
                 0
@@ -28,13 +44,23 @@ pub fn count_overlaps(assignment_pairs: Vec<AssignmentPair>) -> u32 {
         .sum()
 }
 
-fn contains(a: &Range<u32>, b: &Range<u32>) -> bool {
-    a.start <= b.start && b.end <= a.end
+pub fn parse_assignment_pairs(input: &str) -> Result<Vec<AssignmentPair>, ParseAssignmentError> {
+    parse_lines(input)
 }
 
-fn overlaps(a: &Range<u32>, b: &Range<u32>) -> bool {
-    b.start < a.end && a.start <= b.start ||
-    a.start < b.end && b.start <= a.start
+pub fn parse_assignment_groups(input: &str) -> Result<Vec<AssignmentGroup>, ParseAssignmentError> {
+    parse_lines(input)
+}
+
+fn parse_lines<T: FromStr<Err = ParseAssignmentError>>(input: &str) -> Result<Vec<T>, ParseAssignmentError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| line
+            .parse()
+            .map_err(|error: ParseAssignmentError| error.at_line(i + 1))
+        )
+        .collect()
 }
 
 #[cfg(test)]
@@ -77,30 +103,152 @@ mod tests {
         assert_eq!(919, num_overlaps);
     }
 
+    #[test]
+    fn finds_sections_covered_by_pair() {
+        let pair: AssignmentPair = "2-6,4-8".parse().unwrap();
+
+        assert_eq!("[2, 8]", pair.sections().to_string());
+        assert_eq!("[4, 6]", pair.shared_sections().to_string());
+    }
+
+    #[test]
+    fn fails_to_parse_malformed_assignments() {
+        assert_eq!(
+            Err(ParseAssignmentError::ReversedBounds { line: Some(2), start: 7, end: 3 }),
+            parse_assignment_pairs("2-4,6-8\n7-3,1-2")
+        );
+        assert_eq!(
+            Err(ParseAssignmentError::MalformedNumber { line: Some(1), value: "x".to_string() }),
+            parse_assignment_pairs("2-x,6-8")
+        );
+        assert_eq!(
+            Err(ParseAssignmentError::MalformedRange { line: Some(1), value: "6".to_string() }),
+            parse_assignment_pairs("2-4,6")
+        );
+        assert_eq!(
+            Err(ParseAssignmentError::WrongArity { line: Some(3), expected: 2, found: 1 }),
+            parse_assignment_pairs("2-4,6-8\n1-1,2-2\n3-3")
+        );
+    }
+
     fn parse_input(input: &str) -> Vec<AssignmentPair> {
-        input
-            .lines()
-            .map(|line| line.into())
-            .collect()
+        parse_assignment_pairs(input).unwrap()
     }
 }
 
-pub struct AssignmentPair(Range<u32>, Range<u32>);
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AssignmentPair(Interval<u32>, Interval<u32>);
 
-impl From<&str> for AssignmentPair {
-    fn from(value: &str) -> Self {
-        let assignments: Vec<_> = value.split(',').collect();
-        AssignmentPair(
-            to_range(*assignments.iter().nth(0).unwrap()),
-            to_range(*assignments.iter().nth(1).unwrap())
-        )
+impl AssignmentPair {
+    pub fn new(first: Interval<u32>, second: Interval<u32>) -> Self {
+        AssignmentPair(first, second)
+    }
+
+    pub fn first(&self) -> Interval<u32> {
+        self.0
+    }
+
+    pub fn second(&self) -> Interval<u32> {
+        self.1
+    }
+
+    pub fn sections(&self) -> IntervalSet<u32> {
+        [self.0, self.1].into_iter().collect()
+    }
+
+    pub fn shared_sections(&self) -> IntervalSet<u32> {
+        IntervalSet::from(self.0).intersection(&IntervalSet::from(self.1))
     }
 }
 
-fn to_range(value: &str) -> Range<u32> {
-    let range: Vec<_> = value.split('-').collect();
-    Range {
-        start: range.iter().nth(0).unwrap().parse().unwrap(),
-        end: range.iter().nth(1).unwrap().parse::<u32>().unwrap() + 1
+impl Display for AssignmentPair {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{},{}-{}", self.0.start(), self.0.end(), self.1.start(), self.1.end())
     }
-}
\ No newline at end of file
+}
+
+impl FromStr for AssignmentPair {
+    type Err = ParseAssignmentError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match parse_assignments(value)?.as_slice() {
+            [first, second] => Ok(AssignmentPair(*first, *second)),
+            assignments => Err(ParseAssignmentError::WrongArity {
+                line: None,
+                expected: 2,
+                found: assignments.len()
+            })
+        }
+    }
+}
+
+// Reads comma separated inclusive ranges like 2-4,6-8
+pub(crate) fn parse_assignments(value: &str) -> Result<Vec<Interval<u32>>, ParseAssignmentError> {
+    value
+        .split(',')
+        .map(|range| {
+            let (start, end) = range.split_once('-').ok_or_else(|| ParseAssignmentError::MalformedRange {
+                line: None,
+                value: range.to_string()
+            })?;
+            let (start, end) = (parse_section(start)?, parse_section(end)?);
+            Interval::inclusive(start, end).ok_or(ParseAssignmentError::ReversedBounds {
+                line: None,
+                start,
+                end
+            })
+        })
+        .collect()
+}
+
+fn parse_section(value: &str) -> Result<u32, ParseAssignmentError> {
+    value.trim().parse().map_err(|_| ParseAssignmentError::MalformedNumber {
+        line: None,
+        value: value.to_string()
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseAssignmentError {
+    MalformedRange { line: Option<usize>, value: String },
+    MalformedNumber { line: Option<usize>, value: String },
+    ReversedBounds { line: Option<usize>, start: u32, end: u32 },
+    WrongArity { line: Option<usize>, expected: usize, found: usize }
+}
+
+impl ParseAssignmentError {
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ParseAssignmentError::MalformedRange { line, .. }
+            | ParseAssignmentError::MalformedNumber { line, .. }
+            | ParseAssignmentError::ReversedBounds { line, .. }
+            | ParseAssignmentError::WrongArity { line, .. } => *line
+        }
+    }
+
+    pub fn at_line(mut self, line_number: usize) -> Self {
+        match &mut self {
+            ParseAssignmentError::MalformedRange { line, .. }
+            | ParseAssignmentError::MalformedNumber { line, .. }
+            | ParseAssignmentError::ReversedBounds { line, .. }
+            | ParseAssignmentError::WrongArity { line, .. } => *line = Some(line_number)
+        }
+        self
+    }
+}
+
+impl Display for ParseAssignmentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line() {
+            write!(f, "Line {}: ", line)?
+        }
+        match self {
+            ParseAssignmentError::MalformedRange { value, .. } => write!(f, "Expected a range like 2-4 but found '{}'", value),
+            ParseAssignmentError::MalformedNumber { value, .. } => write!(f, "'{}' is not a section number", value),
+            ParseAssignmentError::ReversedBounds { start, end, .. } => write!(f, "Range {}-{} ends before it starts", start, end),
+            ParseAssignmentError::WrongArity { expected, found, .. } => write!(f, "Expected {} assignments but found {}", expected, found)
+        }
+    }
+}
+
+impl Error for ParseAssignmentError {}
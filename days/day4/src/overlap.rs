@@ -0,0 +1,122 @@
+use crate::{AssignmentPair, Interval};
+
+pub fn measure_overlaps(assignment_pairs: &[AssignmentPair]) -> OverlapSummary {
+    OverlapSummary {
+        pairs: assignment_pairs
+            .iter()
+            .map(|pair| pair.measure_overlap())
+            .collect()
+    }
+}
+
+impl AssignmentPair {
+    pub fn measure_overlap(&self) -> PairOverlap {
+        PairOverlap {
+            first: self.first(),
+            second: self.second(),
+            overlap: self.first().intersection(&self.second())
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PairOverlap {
+    pub first: Interval<u32>,
+    pub second: Interval<u32>,
+    pub overlap: Option<Interval<u32>>
+}
+
+impl PairOverlap {
+    // Every shared section gets cleaned twice, so this is also how many sections of effort were wasted
+    pub fn len(&self) -> u64 {
+        self.overlap.map_or(0, |overlap| overlap.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overlap.is_none()
+    }
+
+    pub fn is_full_containment(&self) -> bool {
+        self.overlap == Some(self.first) || self.overlap == Some(self.second)
+    }
+
+    pub fn first_duplicated_fraction(&self) -> f64 {
+        self.len() as f64 / self.first.len() as f64
+    }
+
+    pub fn second_duplicated_fraction(&self) -> f64 {
+        self.len() as f64 / self.second.len() as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlapSummary {
+    pub pairs: Vec<PairOverlap>
+}
+
+impl OverlapSummary {
+    pub fn num_overlapping_pairs(&self) -> usize {
+        self.pairs.iter().filter(|pair| !pair.is_empty()).count()
+    }
+
+    pub fn num_fully_containing_pairs(&self) -> usize {
+        self.pairs.iter().filter(|pair| pair.is_full_containment()).count()
+    }
+
+    pub fn total_assigned_sections(&self) -> u64 {
+        self.pairs
+            .iter()
+            .map(|pair| pair.first.len() + pair.second.len())
+            .sum()
+    }
+
+    pub fn total_wasted_sections(&self) -> u64 {
+        self.pairs.iter().map(|pair| pair.len()).sum()
+    }
+
+    pub fn wasted_fraction(&self) -> f64 {
+        match self.total_assigned_sections() {
+            0 => 0.0,
+            total => self.total_wasted_sections() as f64 / total as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_assignment_pairs;
+
+    #[test]
+    fn measures_overlap_of_pair() {
+        let overlap = "2-8,3-7".parse::<AssignmentPair>().unwrap().measure_overlap();
+
+        assert_eq!(Interval::inclusive(3, 7), overlap.overlap);
+        assert_eq!(5, overlap.len());
+        assert!(overlap.is_full_containment());
+        assert_eq!(5.0 / 7.0, overlap.first_duplicated_fraction());
+        assert_eq!(1.0, overlap.second_duplicated_fraction());
+    }
+
+    #[test]
+    fn measures_pairs_without_overlap() {
+        let overlap = "2-4,6-8".parse::<AssignmentPair>().unwrap().measure_overlap();
+
+        assert_eq!(None, overlap.overlap);
+        assert_eq!(0, overlap.len());
+        assert_eq!(0.0, overlap.first_duplicated_fraction());
+    }
+
+    #[test]
+    fn totals_wasted_effort_in_example_input() {
+        let pairs = parse_assignment_pairs(include_str!("example.txt")).unwrap();
+
+        let summary = measure_overlaps(&pairs);
+
+        assert_eq!(4, summary.num_overlapping_pairs());
+        assert_eq!(2, summary.num_fully_containing_pairs());
+        assert_eq!(42, summary.total_assigned_sections());
+        assert_eq!(10, summary.total_wasted_sections());
+        assert_eq!(10.0 / 42.0, summary.wasted_fraction());
+    }
+}
@@ -0,0 +1,483 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use crate::{AssignmentPair, Interval, IntervalSet};
+
+// Reassigns each pair on its own, so elves only ever give up sections to their partner
+pub fn reassign_within_pairs(assignment_pairs: &[AssignmentPair]) -> Result<Reassignment, ReassignmentError> {
+    let mut reassignment = Reassignment::default();
+    for (pair_index, pair) in assignment_pairs.iter().enumerate() {
+        let (changed_sections, assignments) = reassign(&[pair.first(), pair.second()])
+            .map_err(|error| error.for_pair(pair_index))?;
+        reassignment.changed_sections += changed_sections;
+        reassignment.assignment_pairs.push(AssignmentPair::new(assignments[0], assignments[1]))
+    }
+    Ok(reassignment)
+}
+
+// Removes every overlap in the camp at once, which can also shift sections between pairs.
+// Elves keep the order of their assignments, so this is the fewest changes among
+// reassignments that keep that order, not among every possible one.
+pub fn reassign_camp(assignment_pairs: &[AssignmentPair]) -> Result<Reassignment, ReassignmentError> {
+    let assignments: Vec<_> = assignment_pairs
+        .iter()
+        .flat_map(|pair| [pair.first(), pair.second()])
+        .collect();
+    let (changed_sections, assignments) = reassign(&assignments)?;
+    Ok(Reassignment {
+        assignment_pairs: assignments
+            .chunks_exact(2)
+            .map(|pair| AssignmentPair::new(pair[0], pair[1]))
+            .collect(),
+        changed_sections
+    })
+}
+
+// The covered sections have to be split into consecutive blocks, one per elf, so each
+// elf keeps a non empty assignment and nothing goes uncovered. Elves keep the order of
+// their original assignments across the whole camp, which can move them into another
+// run of sections, and a dynamic program over the block boundaries finds the split that
+// moves the fewest sections. With only two elves both orders are tried, which makes
+// pairs truly minimal.
+fn reassign(assignments: &[Interval<u32>]) -> Result<(u64, Vec<Interval<u32>>), ReassignmentError> {
+    let coverage: IntervalSet<u32> = assignments.iter().copied().collect();
+    let runs = coverage.intervals();
+    let num_sections = runs.iter().map(|run| run.len()).sum();
+    let too_few_sections = ReassignmentError::TooFewSections {
+        pair_index: None,
+        num_sections,
+        num_elves: assignments.len()
+    };
+    if assignments.len() as u64 > num_sections {
+        return Err(too_few_sections)
+    }
+
+    let mut elves: Vec<_> = (0..assignments.len()).collect();
+    elves.sort_by_key(|i| (assignments[*i].start(), assignments[*i].end(), *i));
+    let mut orders = vec![elves.clone()];
+    if elves.len() == 2 {
+        orders.push(elves.iter().rev().copied().collect())
+    }
+    let (cost, order, blocks) = orders
+        .into_iter()
+        .filter_map(|order| {
+            let elf_assignments: Vec<_> = order.iter().map(|i| assignments[*i]).collect();
+            split_sections(runs, &elf_assignments).map(|(cost, blocks)| (cost, order, blocks))
+        })
+        .min_by_key(|(cost, _, _)| *cost)
+        .ok_or(too_few_sections)?;
+    let mut reassigned = assignments.to_vec();
+    for (i, block) in order.into_iter().zip(blocks) {
+        reassigned[i] = block
+    }
+    Ok((cost, reassigned))
+}
+
+// Sections between consecutive elf and run boundaries are interchangeable, so rather than
+// trying every split point the dynamic program walks those segments. kept[j] is the
+// most original sections the elves can keep so far with elf j holding the last section
+// reached. Crossing a segment either leaves elf j holding all of it or hands at least
+// one section to each of the next elves, with the spare sections going to one that
+// already works there, and sliding window maxima find the best elf to hand over from.
+// The first segment of a run always starts with a new elf, since blocks can't span gaps.
+fn split_sections(runs: &[Interval<u32>], elves: &[Interval<u32>]) -> Option<(u64, Vec<Interval<u32>>)> {
+    let num_elves = elves.len();
+    let mut boundaries: Vec<u64> = elves
+        .iter()
+        .chain(runs)
+        .flat_map(|interval| [interval.start() as u64, interval.end() as u64 + 1])
+        .collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    // Each segment also notes whether it starts a run, skipping the gaps between runs
+    let segments: Vec<_> = boundaries
+        .windows(2)
+        .filter_map(|bounds| {
+            let run = runs.get(runs.partition_point(|run| (run.end() as u64) < bounds[0]))?;
+            (run.start() as u64 <= bounds[0]).then_some((bounds[0], bounds[1], run.start() as u64 == bounds[0]))
+        })
+        .collect();
+    let containing = |(from, to): (u64, u64)| -> Vec<bool> {
+        elves.iter().map(|elf| elf.start() as u64 <= from && to <= elf.end() as u64 + 1).collect()
+    };
+
+    let mut kept: Vec<Option<u64>> = vec![None; num_elves];
+    // handed_over_by[s][j] is the elf that held the last section before segment s, None at the very start
+    let mut handed_over_by = Vec::with_capacity(segments.len());
+    for (s, &(from, to, new_run)) in segments.iter().enumerate() {
+        let len = to - from;
+        let contains = containing((from, to));
+        // num_containing[k] counts the elves before k that hold the whole segment
+        let num_containing: Vec<u64> = std::iter::once(0)
+            .chain(contains.iter().scan(0, |count, contains| {
+                *count += *contains as u64;
+                Some(*count)
+            }))
+            .collect();
+        let mut next_kept = vec![None; num_elves];
+        let mut previous_elves = vec![None; num_elves];
+        let mut with_spare = SlidingMax::default();
+        let mut without_spare = SlidingMax::default();
+        let mut last_containing = None;
+        for j in 0..num_elves {
+            if contains[j] {
+                last_containing = Some(j)
+            }
+            // Elves from the window's start up to j each need a section of their own
+            let first_possible = j.saturating_sub(usize::try_from(len).unwrap_or(usize::MAX));
+            let mut candidates = [None; 3];
+            if s == 0 {
+                if (j as u64) < len {
+                    let spare = if num_containing[j + 1] > 0 { len - j as u64 - 1 } else { 0 };
+                    candidates[0] = Some((num_containing[j + 1] + spare, None))
+                }
+            } else {
+                if let Some(kept) = kept[j].filter(|_| !new_run) {
+                    candidates[0] = Some((kept + if contains[j] { len } else { 0 }, Some(j)))
+                }
+                // Handing over from an elf after the last one holding the segment leaves
+                // nobody to take the spare sections, and so does handing over from that
+                // elf itself when it finished in the previous run
+                let value = |i: usize, with_spare: bool| kept[i].map(|kept| {
+                    kept as i128 - num_containing[i + 1] as i128 + if with_spare { i as i128 } else { 0 }
+                });
+                let spare_takers = last_containing.map_or(0, |last| if new_run { last } else { last + 1 });
+                if let Some((i, value)) = with_spare.max(first_possible, spare_takers.min(j), |i| value(i, true)) {
+                    let total = value + num_containing[j + 1] as i128 + len as i128 - j as i128;
+                    candidates[1] = Some((total as u64, Some(i)))
+                }
+                if let Some((i, value)) = without_spare.max(first_possible.max(spare_takers), j, |i| value(i, false)) {
+                    candidates[2] = Some(((value + num_containing[j + 1] as i128) as u64, Some(i)))
+                }
+            }
+            if let Some((best, previous_elf)) = candidates.into_iter().flatten().max_by_key(|(kept, _)| *kept) {
+                next_kept[j] = Some(best);
+                previous_elves[j] = previous_elf
+            }
+        }
+        kept = next_kept;
+        handed_over_by.push(previous_elves);
+    }
+    kept[num_elves - 1]?;
+
+    // Walks back through the segments to find how many sections each elf got in each
+    let mut allotments = Vec::with_capacity(segments.len());
+    let mut elf = num_elves - 1;
+    for (s, &(from, to, new_run)) in segments.iter().enumerate().rev() {
+        let previous_elf = handed_over_by[s][elf];
+        let len = to - from;
+        let mut allotment = Vec::new();
+        if previous_elf == Some(elf) {
+            allotment.push((elf, len))
+        } else {
+            let first_new = previous_elf.map_or(0, |previous| previous + 1);
+            let spare = len - (elf + 1 - first_new) as u64;
+            let contains = containing((from, to));
+            let taker = previous_elf
+                .filter(|_| !new_run)
+                .into_iter()
+                .chain(first_new..=elf)
+                .find(|i| contains[*i])
+                .unwrap_or(elf);
+            if let Some(previous) = previous_elf.filter(|previous| *previous == taker) {
+                allotment.push((previous, spare))
+            }
+            allotment.extend((first_new..=elf).map(|i| (i, 1 + if i == taker { spare } else { 0 })))
+        }
+        allotments.push(allotment);
+        if let Some(previous_elf) = previous_elf {
+            elf = previous_elf
+        }
+    }
+
+    let mut bounds = vec![(u64::MAX, 0); num_elves];
+    for (&(from, _, _), allotment) in segments.iter().zip(allotments.into_iter().rev()) {
+        let mut next_section = from;
+        for (i, num_sections) in allotment.into_iter().filter(|(_, num_sections)| *num_sections > 0) {
+            bounds[i].0 = bounds[i].0.min(next_section);
+            next_section += num_sections;
+            bounds[i].1 = next_section - 1
+        }
+    }
+    let blocks = bounds
+        .into_iter()
+        .map(|(start, end)| Interval::inclusive(u32::try_from(start).ok()?, u32::try_from(end).ok()?))
+        .collect::<Option<Vec<_>>>()?;
+    let cost = elves.iter().zip(&blocks).map(|(elf, block)| count_changes(elf, block)).sum();
+    Some((cost, blocks))
+}
+
+// Maximum over a window of indices whose bounds only ever move forwards
+#[derive(Default)]
+struct SlidingMax {
+    window: VecDeque<(usize, i128)>,
+    next_index: usize
+}
+
+impl SlidingMax {
+    fn max(&mut self, start: usize, end: usize, value: impl Fn(usize) -> Option<i128>) -> Option<(usize, i128)> {
+        while self.next_index < end {
+            if let Some(value) = value(self.next_index) {
+                while self.window.back().is_some_and(|(_, back)| *back < value) {
+                    self.window.pop_back();
+                }
+                self.window.push_back((self.next_index, value))
+            }
+            self.next_index += 1
+        }
+        while self.window.front().is_some_and(|(i, _)| *i < start) {
+            self.window.pop_front();
+        }
+        self.window.front().copied()
+    }
+}
+
+// Sections the elf stops working on plus sections it starts working on
+fn count_changes(original: &Interval<u32>, reassigned: &Interval<u32>) -> u64 {
+    let kept = original.intersection(reassigned).map_or(0, |kept| kept.len());
+    original.len() + reassigned.len() - 2 * kept
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Reassignment {
+    pub assignment_pairs: Vec<AssignmentPair>,
+    pub changed_sections: u64
+}
+
+// Writes the new assignments in the same format as the puzzle input
+impl Display for Reassignment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, pair) in self.assignment_pairs.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?
+            }
+            write!(f, "{}", pair)?
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReassignmentError {
+    TooFewSections { pair_index: Option<usize>, num_sections: u64, num_elves: usize }
+}
+
+impl ReassignmentError {
+    fn for_pair(self, pair_index: usize) -> Self {
+        match self {
+            ReassignmentError::TooFewSections { num_sections, num_elves, .. } => ReassignmentError::TooFewSections {
+                pair_index: Some(pair_index),
+                num_sections,
+                num_elves
+            }
+        }
+    }
+}
+
+impl Display for ReassignmentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReassignmentError::TooFewSections { pair_index, num_sections, num_elves } => {
+                if let Some(pair_index) = pair_index {
+                    write!(f, "Pair {}: ", pair_index)?
+                }
+                write!(f, "{} elves can't each keep one of {} sections without overlapping", num_elves, num_sections)
+            }
+        }
+    }
+}
+
+impl Error for ReassignmentError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_assignment_pairs;
+
+    #[test]
+    fn reassigns_example_input_within_pairs() {
+        let pairs = parse_input(include_str!("example.txt"));
+
+        let reassignment = reassign_within_pairs(&pairs).unwrap();
+
+        assert_eq!("2-4,6-8\n2-3,4-5\n5-7,8-9\n2-7,8-8\n6-6,4-5\n2-6,7-8", reassignment.to_string());
+        assert_eq!(12, reassignment.changed_sections);
+        assert!(reassignment.assignment_pairs.iter().all(|pair| !pair.first().overlaps(&pair.second())));
+    }
+
+    #[test]
+    fn reassigns_whole_camp_keeping_coverage() {
+        let pairs = parse_input("2-6,4-8\n5-9,20-25\n21-21,1-3");
+
+        let reassignment = reassign_camp(&pairs).unwrap();
+
+        assert_eq!("4-6,7-8\n9-9,20-24\n25-25,1-3", reassignment.to_string());
+        assert_eq!(12, reassignment.changed_sections);
+        assert_eq!(0, count_overlaps(&reassignment));
+        assert_eq!(
+            pairs.iter().map(|pair| pair.sections()).reduce(|acc, curr| acc.union(&curr)),
+            reassignment.assignment_pairs.iter().map(|pair| pair.sections()).reduce(|acc, curr| acc.union(&curr))
+        );
+    }
+
+    #[test]
+    fn fails_when_elves_outnumber_sections() {
+        let pairs = parse_input("1-2,3-4\n6-6,6-6");
+
+        let result = reassign_within_pairs(&pairs);
+
+        assert_eq!(
+            Err(ReassignmentError::TooFewSections { pair_index: Some(1), num_sections: 1, num_elves: 2 }),
+            result
+        );
+    }
+
+    #[test]
+    fn reassigns_huge_spans() {
+        let pairs = parse_input("1-1000000000,5-6");
+
+        let reassignment = reassign_within_pairs(&pairs).unwrap();
+
+        assert_eq!("1-999999999,1000000000-1000000000", reassignment.to_string());
+        assert_eq!(4, reassignment.changed_sections);
+    }
+
+    #[test]
+    fn reassigns_sections_up_to_the_last() {
+        let pairs = parse_input("4294967290-4294967295,4294967295-4294967295");
+
+        let reassignment = reassign_within_pairs(&pairs).unwrap();
+
+        assert_eq!("4294967290-4294967294,4294967295-4294967295", reassignment.to_string());
+        assert_eq!(1, reassignment.changed_sections);
+    }
+
+    #[test]
+    fn reassigns_actual_input_within_pairs() {
+        let pairs = parse_input(include_str!("actual.txt"));
+
+        let result = reassign_within_pairs(&pairs);
+
+        assert_eq!(
+            Err(ReassignmentError::TooFewSections { pair_index: Some(213), num_sections: 1, num_elves: 2 }),
+            result
+        );
+        for pair in pairs {
+            let reassignment = reassign_within_pairs(std::slice::from_ref(&pair));
+            assert_eq!(
+                fewest_changes(&[pair.first(), pair.second()], false),
+                reassignment.map(|reassignment| reassignment.changed_sections).ok()
+            );
+        }
+    }
+
+    #[test]
+    fn fails_to_reassign_actual_camp() {
+        let pairs = parse_input(include_str!("actual.txt"));
+
+        let result = reassign_camp(&pairs);
+
+        assert_eq!(
+            Err(ReassignmentError::TooFewSections {
+                pair_index: None,
+                num_sections: 99,
+                num_elves: 2000
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn moves_elves_into_other_runs_of_sections() {
+        let pairs = parse_input("5-5,5-5\n8-10,100-100");
+
+        let reassignment = reassign_camp(&pairs).unwrap();
+
+        assert_eq!("5-5,8-8\n9-10,100-100", reassignment.to_string());
+        assert_eq!(3, reassignment.changed_sections);
+    }
+
+    #[test]
+    fn matches_brute_force_in_order_on_small_camps() {
+        let assignments: Vec<_> = (1..=4)
+            .flat_map(|start| (start..=4).map(move |end| Interval::inclusive(start, end).unwrap()))
+            .collect();
+        let all_pairs: Vec<_> = assignments
+            .iter()
+            .flat_map(|first| assignments.iter().map(|second| AssignmentPair::new(*first, *second)))
+            .collect();
+        for pairs in all_pairs.iter().flat_map(|first| all_pairs.iter().map(|second| vec![*first, *second])) {
+            let assignments: Vec<_> = pairs.iter().flat_map(|pair| [pair.first(), pair.second()]).collect();
+            let fewest = fewest_changes(&assignments, false);
+            let fewest_in_order = fewest_changes(&assignments, true);
+
+            let reassignment = reassign_camp(&pairs);
+
+            let Ok(reassignment) = reassignment else {
+                assert_eq!(None, fewest, "{:?}", pairs);
+                continue
+            };
+            assert_eq!(fewest_in_order, Some(reassignment.changed_sections), "{:?}", pairs);
+            assert!(fewest <= fewest_in_order, "{:?}", pairs);
+            assert_eq!(0, count_overlaps(&reassignment));
+            assert_eq!(
+                pairs.iter().map(|pair| pair.sections()).reduce(|acc, curr| acc.union(&curr)),
+                reassignment.assignment_pairs.iter().map(|pair| pair.sections()).reduce(|acc, curr| acc.union(&curr))
+            );
+        }
+    }
+
+    fn count_overlaps(reassignment: &Reassignment) -> usize {
+        let assignments: Vec<_> = reassignment.assignment_pairs
+            .iter()
+            .flat_map(|pair| [pair.first(), pair.second()])
+            .collect();
+        assignments
+            .iter()
+            .enumerate()
+            .map(|(i, a)| assignments[i + 1..].iter().filter(|b| a.overlaps(b)).count())
+            .sum()
+    }
+
+    fn parse_input(input: &str) -> Vec<AssignmentPair> {
+        parse_assignment_pairs(input).unwrap()
+    }
+
+    // Tries every elf on every block of the covered sections, or when kept in order only
+    // the first unassigned elf by start and end
+    fn fewest_changes(assignments: &[Interval<u32>], in_order: bool) -> Option<u64> {
+        let mut assignments = assignments.to_vec();
+        assignments.sort_by_key(|assignment| (assignment.start(), assignment.end()));
+        let coverage: IntervalSet<u32> = assignments.iter().copied().collect();
+        let sections: Vec<u32> = coverage.intervals().iter().flat_map(|run| run.start()..=run.end()).collect();
+        fewest_changes_from(&assignments, &sections, &mut vec![false; assignments.len()], in_order)
+    }
+
+    fn fewest_changes_from(assignments: &[Interval<u32>], sections: &[u32], assigned: &mut [bool], in_order: bool) -> Option<u64> {
+        let num_unassigned = assigned.iter().filter(|assigned| !**assigned).count();
+        if num_unassigned == 0 || num_unassigned > sections.len() {
+            return (num_unassigned == 0 && sections.is_empty()).then_some(0)
+        }
+        let mut fewest = None;
+        for len in 1..=sections.len() {
+            if len > 1 && sections[len - 1] != sections[len - 2] + 1 {
+                break
+            }
+            if num_unassigned == 1 && len < sections.len() {
+                continue
+            }
+            let block = Interval::inclusive(sections[0], sections[len - 1]).unwrap();
+            let unassigned: Vec<_> = (0..assignments.len()).filter(|i| !assigned[*i]).collect();
+            for &i in if in_order { &unassigned[..1] } else { &unassigned[..] } {
+                assigned[i] = true;
+                if let Some(rest) = fewest_changes_from(assignments, &sections[len..], assigned, in_order) {
+                    let changes = count_changes(&assignments[i], &block) + rest;
+                    fewest = Some(fewest.map_or(changes, |fewest: u64| fewest.min(changes)))
+                }
+                assigned[i] = false
+            }
+        }
+        fewest
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{AssignmentPair, Interval};
+
+// Draws each pair the way the puzzle does, one row of dots and section digits per elf
+// over an axis shared by every pair, with a row of carets under any overlap. Axes
+// wider than max_width get squeezed so each column stands for several sections, in
+// which case columns show # when fully assigned and + when only partly assigned.
+pub fn render_assignment_pairs(assignment_pairs: &[AssignmentPair], max_width: usize) -> String {
+    let Some(axis) = Axis::new(assignment_pairs, max_width) else {
+        return String::new()
+    };
+    let mut lines = Vec::new();
+    if axis.sections_per_column > 1 {
+        lines.push(format!(
+            "Sections {}-{}, {} per column",
+            axis.start,
+            axis.end,
+            axis.sections_per_column
+        ))
+    }
+    for (i, pair) in assignment_pairs.iter().enumerate() {
+        if i > 0 || axis.sections_per_column > 1 {
+            lines.push(String::new())
+        }
+        for assignment in [pair.first(), pair.second()] {
+            lines.push(format!(
+                "{}  {}-{}",
+                axis.draw(|column| axis.draw_assignment(&assignment, column)),
+                assignment.start(),
+                assignment.end()
+            ))
+        }
+        if let Some(overlap) = pair.first().intersection(&pair.second()) {
+            let containment = if overlap == pair.first() || overlap == pair.second() {
+                ", fully contained"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "{}  overlap {}-{}{}",
+                axis.draw(|column| if column.overlaps(&overlap) { '^' } else { '.' }),
+                overlap.start(),
+                overlap.end(),
+                containment
+            ))
+        }
+    }
+    lines.join("\n")
+}
+
+struct Axis {
+    start: u32,
+    end: u32,
+    sections_per_column: u32
+}
+
+impl Axis {
+    fn new(assignment_pairs: &[AssignmentPair], max_width: usize) -> Option<Self> {
+        let assignments = assignment_pairs.iter().flat_map(|pair| [pair.first(), pair.second()]);
+        let start = assignments.clone().map(|assignment| assignment.start()).min()?;
+        let end = assignments.map(|assignment| assignment.end()).max()?;
+        let max_width = max_width.max(1) as u64;
+        // Like the puzzle, narrow axes count from the first section
+        let start = if (end as u64) < max_width { start.min(1) } else { start };
+        let num_sections = end as u64 - start as u64 + 1;
+        // All 2^32 sections can't share a column, so they spill over into a second one
+        let sections_per_column = u32::try_from(num_sections.div_ceil(max_width).max(1)).unwrap_or(u32::MAX);
+        Some(Axis {
+            start,
+            end,
+            sections_per_column
+        })
+    }
+
+    fn draw(&self, draw_column: impl Fn(Interval<u32>) -> char) -> String {
+        let num_columns = (self.end - self.start) / self.sections_per_column + 1;
+        (0..num_columns)
+            .map(|i| {
+                let column_start = self.start + i * self.sections_per_column;
+                let column_end = column_start.saturating_add(self.sections_per_column - 1).min(self.end);
+                draw_column(Interval::inclusive(column_start, column_end).unwrap())
+            })
+            .collect()
+    }
+
+    fn draw_assignment(&self, assignment: &Interval<u32>, column: Interval<u32>) -> char {
+        match assignment.intersection(&column) {
+            None => '.',
+            Some(_) if self.sections_per_column == 1 => char::from_digit(column.start() % 10, 10).unwrap(),
+            Some(covered) if covered == column => '#',
+            Some(_) => '+'
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_assignment_pairs;
+
+    #[test]
+    fn renders_example_input_like_the_puzzle() {
+        let pairs = parse_assignment_pairs(include_str!("example.txt")).unwrap();
+
+        let rendering = render_assignment_pairs(&pairs, 80);
+
+        assert_eq!(
+            [
+                ".234.....  2-4",
+                ".....678.  6-8",
+                "",
+                ".23......  2-3",
+                "...45....  4-5",
+                "",
+                "....567..  5-7",
+                "......789  7-9",
+                "......^..  overlap 7-7",
+                "",
+                ".2345678.  2-8",
+                "..34567..  3-7",
+                "..^^^^^..  overlap 3-7, fully contained",
+                "",
+                ".....6...  6-6",
+                "...456...  4-6",
+                ".....^...  overlap 6-6, fully contained",
+                "",
+                ".23456...  2-6",
+                "...45678.  4-8",
+                "...^^^...  overlap 4-6"
+            ].join("\n"),
+            rendering
+        );
+    }
+
+    #[test]
+    fn scales_wide_ranges_to_fit() {
+        let pairs = parse_assignment_pairs("100-159,140-199").unwrap();
+
+        let rendering = render_assignment_pairs(&pairs, 10);
+
+        assert_eq!(
+            [
+                "Sections 100-199, 10 per column",
+                "",
+                "######....  100-159",
+                "....######  140-199",
+                "....^^....  overlap 140-159"
+            ].join("\n"),
+            rendering
+        );
+    }
+
+    #[test]
+    fn scales_the_full_section_range_down_to_the_minimum_width() {
+        let pairs = parse_assignment_pairs("0-4294967295,5-6").unwrap();
+
+        let rendering = render_assignment_pairs(&pairs, 1);
+
+        assert_eq!(
+            [
+                "Sections 0-4294967295, 4294967295 per column",
+                "",
+                "##  0-4294967295",
+                "+.  5-6",
+                "^.  overlap 5-6, fully contained"
+            ].join("\n"),
+            rendering
+        );
+    }
+}
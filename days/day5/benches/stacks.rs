@@ -0,0 +1,64 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use day5::{rearrange_crates_in, rearrange_crates_with, ChunkedStacks, Crane, CrateMover9000, CrateMover9001, Operation};
+
+const NUM_STACKS: usize = 9;
+const STACK_HEIGHT: usize = 20_000;
+const NUM_OPERATIONS: usize = 5_000;
+const CRATES: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Tall stacks and long moves, tracking heights so every operation can run
+fn generate_input() -> (Vec<Vec<char>>, Vec<Operation>) {
+    let mut seed: u64 = 0x2022_1205;
+    let mut next_index = |len: usize| {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((seed >> 33) % len as u64) as usize
+    };
+    let crate_stacks: Vec<Vec<char>> = (0..NUM_STACKS)
+        .map(|_| (0..STACK_HEIGHT).map(|_| CRATES[next_index(CRATES.len())] as char).collect())
+        .collect();
+    let mut heights = [STACK_HEIGHT; NUM_STACKS];
+    let operations = (0..NUM_OPERATIONS)
+        .map(|_| {
+            let source = (0..NUM_STACKS).max_by_key(|i| (heights[*i], next_index(NUM_STACKS))).unwrap();
+            let target = (source + 1 + next_index(NUM_STACKS - 1)) % NUM_STACKS;
+            let num_crates = 1 + next_index(heights[source]);
+            heights[source] -= num_crates;
+            heights[target] += num_crates;
+            Operation::new(num_crates, source, target)
+        })
+        .collect();
+    (crate_stacks, operations)
+}
+
+fn rearrange(c: &mut Criterion, name: &str, crane: &impl Crane) {
+    let (crate_stacks, operations) = generate_input();
+    assert_eq!(
+        rearrange_crates_with(crate_stacks.clone(), operations.clone(), crane),
+        rearrange_crates_in::<ChunkedStacks>(crate_stacks.clone(), operations.clone(), crane)
+    );
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    group.bench_function("vectors", |b| b.iter_batched(
+        || (crate_stacks.clone(), operations.clone()),
+        |(crate_stacks, operations)| rearrange_crates_with(black_box(crate_stacks), black_box(operations), crane),
+        BatchSize::LargeInput
+    ));
+    group.bench_function("chunked", |b| b.iter_batched(
+        || (crate_stacks.clone(), operations.clone()),
+        |(crate_stacks, operations)| rearrange_crates_in::<ChunkedStacks>(black_box(crate_stacks), black_box(operations), crane),
+        BatchSize::LargeInput
+    ));
+    group.finish();
+}
+
+fn crate_mover_9000(c: &mut Criterion) {
+    rearrange(c, "crate_mover_9000", &CrateMover9000)
+}
+
+fn crate_mover_9001(c: &mut Criterion) {
+    rearrange(c, "crate_mover_9001", &CrateMover9001)
+}
+
+criterion_group!(benches, crate_mover_9000, crate_mover_9001);
+criterion_main!(benches);
@@ -0,0 +1,259 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crate::crane::lift_sizes;
+use crate::diagram::{label_width, render_cell, render_footer};
+use crate::{Crane, Simulation};
+
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(1);
+
+// Plays a simulation back in the terminal, drawing each lift of the crane as its crates
+// rise clear of the stacks, travel across and come down onto the target stack, one lift
+// for every lift_size the crane gives.
+#[derive(Debug, Clone)]
+pub struct Animation<C: Crane, T = char> {
+    simulation: Simulation<C, T>,
+    frame_delay: Duration,
+    paused: bool,
+    label_width: usize,
+    // Level held crates travel at, just above the tallest any stack gets
+    clearance: usize,
+    canvas_height: usize
+}
+
+impl<C: Crane, T: Clone + Display> Animation<C, T> {
+    pub fn new(simulation: Simulation<C, T>) -> Self {
+        let mut heights: Vec<_> = simulation.crate_stacks().iter().map(|stack| stack.len()).collect();
+        let mut clearance = heights.iter().copied().max().unwrap_or(0);
+        let mut max_lift = 0;
+        for operation in &simulation.operations()[simulation.step()..] {
+            heights[operation.source_stack_index] -= operation.num_crates_to_move;
+            heights[operation.target_stack_index] += operation.num_crates_to_move;
+            clearance = clearance.max(heights[operation.target_stack_index]);
+            max_lift = max_lift.max(lift_sizes(simulation.crane(), operation.num_crates_to_move).max().unwrap_or(0))
+        }
+        Animation {
+            label_width: label_width(simulation.crate_stacks()),
+            simulation,
+            frame_delay: Duration::from_millis(100),
+            paused: false,
+            clearance,
+            canvas_height: clearance + max_lift
+        }
+    }
+
+    pub fn with_frame_delay(mut self, frame_delay: Duration) -> Self {
+        self.frame_delay = frame_delay.max(MIN_FRAME_DELAY);
+        self
+    }
+
+    pub fn frame_delay(&self) -> Duration {
+        self.frame_delay
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn simulation(&self) -> &Simulation<C, T> {
+        &self.simulation
+    }
+
+    pub fn into_simulation(self) -> Simulation<C, T> {
+        self.simulation
+    }
+
+    // Every frame of the next operation, starting with the stacks before it and ending
+    // with them after it. Empty once the simulation has finished.
+    pub fn frames_for_next_step(&self) -> Vec<String> {
+        let Some(operation) = self.simulation.operations().get(self.simulation.step()) else {
+            return Vec::new()
+        };
+        let mut crate_stacks = self.simulation.crate_stacks().to_vec();
+        let mut frames = vec![self.draw_frame(&crate_stacks, None)];
+        let (source, target) = (operation.source_stack_index, operation.target_stack_index);
+        for lift_size in lift_sizes(self.simulation.crane(), operation.num_crates_to_move) {
+            let source_stack = &mut crate_stacks[source];
+            let crates = source_stack.split_off(source_stack.len() - lift_size);
+            let mut held = HeldCrates {
+                crates,
+                stack_index: source,
+                level: crate_stacks[source].len()
+            };
+            while held.level < self.clearance {
+                held.level += 1;
+                frames.push(self.draw_frame(&crate_stacks, Some(&held)))
+            }
+            while held.stack_index != target {
+                if held.stack_index < target {
+                    held.stack_index += 1
+                } else {
+                    held.stack_index -= 1
+                }
+                frames.push(self.draw_frame(&crate_stacks, Some(&held)))
+            }
+            while held.level > crate_stacks[target].len() {
+                held.level -= 1;
+                frames.push(self.draw_frame(&crate_stacks, Some(&held)))
+            }
+            crate_stacks[target].append(&mut held.crates);
+            frames.pop();
+            frames.push(self.draw_frame(&crate_stacks, None))
+        }
+        frames
+    }
+
+    // Draws every remaining step to out, clearing the screen between frames. Controls
+    // are checked before each frame, and while paused playback waits on them.
+    pub fn play(&mut self, out: &mut impl Write, controls: &Receiver<Control>) -> io::Result<()> {
+        while !self.simulation.is_finished() {
+            let frames = self.frames_for_next_step();
+            let operation = self.simulation.operations()[self.simulation.step()];
+            for frame in frames {
+                loop {
+                    let control = if self.paused {
+                        controls.recv().ok()
+                    } else {
+                        match controls.try_recv() {
+                            Ok(control) => Some(control),
+                            Err(TryRecvError::Empty) => break,
+                            Err(TryRecvError::Disconnected) => None
+                        }
+                    };
+                    match control {
+                        Some(Control::Stop) => return Ok(()),
+                        Some(control) => self.apply(control),
+                        // Nobody is left to resume playback
+                        None if self.paused => return Ok(()),
+                        None => break
+                    }
+                }
+                write!(
+                    out,
+                    "{}{}\n\nStep {}/{}: {}\n",
+                    CLEAR_SCREEN,
+                    frame,
+                    self.simulation.step() + 1,
+                    self.simulation.num_steps(),
+                    operation
+                )?;
+                out.flush()?;
+                thread::sleep(self.frame_delay)
+            }
+            self.simulation.step_forward();
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, control: Control) {
+        match control {
+            Control::Pause => self.paused = true,
+            Control::Resume => self.paused = false,
+            Control::TogglePause => self.paused = !self.paused,
+            Control::Faster => self.frame_delay = (self.frame_delay / 2).max(MIN_FRAME_DELAY),
+            Control::Slower => self.frame_delay *= 2,
+            Control::SetFrameDelay(frame_delay) => self.frame_delay = frame_delay.max(MIN_FRAME_DELAY),
+            Control::Stop => {}
+        }
+    }
+
+    fn draw_frame(&self, crate_stacks: &[Vec<T>], held: Option<&HeldCrates<T>>) -> String {
+        let rows = (0..self.canvas_height).rev().map(|level| (0..crate_stacks.len())
+            .map(|stack_index| {
+                let held_crate = held
+                    .filter(|held| held.stack_index == stack_index && level >= held.level)
+                    .and_then(|held| held.crates.get(level - held.level));
+                render_cell(held_crate.or(crate_stacks[stack_index].get(level)), self.label_width)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+        );
+        rows.chain([render_footer(crate_stacks.len(), self.label_width)]).collect::<Vec<_>>().join("\n")
+    }
+}
+
+// Sent by whatever reads the keyboard to steer playback
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Control {
+    Pause,
+    Resume,
+    TogglePause,
+    Faster,
+    Slower,
+    SetFrameDelay(Duration),
+    Stop
+}
+
+// Crates hanging from the crane, listed bottom first, with the level their bottom crate is at
+struct HeldCrates<T> {
+    crates: Vec<T>,
+    stack_index: usize,
+    level: usize
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::tests::parse_input;
+    use crate::{CrateMover9000, CrateMover9001, Operation};
+
+    #[test]
+    fn draws_crates_lifting_and_moving() {
+        let simulation = Simulation::new(vec![vec!['A', 'B'], vec![]], vec![Operation::new(2, 0, 1)], CrateMover9001).unwrap();
+        let animation = Animation::new(simulation);
+
+        let frames = animation.frames_for_next_step();
+
+        assert_eq!(
+            vec![
+                ["       ", "       ", "[B]    ", "[A]    ", " 1   2 "].join("\n"),
+                ["       ", "[B]    ", "[A]    ", "       ", " 1   2 "].join("\n"),
+                ["[B]    ", "[A]    ", "       ", "       ", " 1   2 "].join("\n"),
+                ["    [B]", "    [A]", "       ", "       ", " 1   2 "].join("\n"),
+                ["       ", "    [B]", "    [A]", "       ", " 1   2 "].join("\n"),
+                ["       ", "       ", "    [B]", "    [A]", " 1   2 "].join("\n")
+            ],
+            frames
+        );
+    }
+
+    #[test]
+    fn lifts_one_crate_at_a_time_for_crate_mover_9000() {
+        let simulation = Simulation::new(vec![vec!['A', 'B'], vec![]], vec![Operation::new(2, 0, 1)], CrateMover9000).unwrap();
+        let animation = Animation::new(simulation);
+
+        let frames = animation.frames_for_next_step();
+
+        assert_eq!(["[B]    ", "       ", "[A]    ", " 1   2 "].join("\n"), frames[1]);
+        assert_eq!(["       ", "    [A]", "    [B]", " 1   2 "].join("\n"), frames[frames.len() - 1]);
+    }
+
+    #[test]
+    fn plays_to_the_end_and_stops_on_request() {
+        let (crate_stacks, operations) = parse_input(include_str!("example.txt"));
+        let simulation = Simulation::new(crate_stacks, operations, CrateMover9000).unwrap();
+        let (controls, receiver) = mpsc::channel();
+        let mut output = Vec::new();
+
+        controls.send(Control::TogglePause).unwrap();
+        controls.send(Control::SetFrameDelay(Duration::ZERO)).unwrap();
+        controls.send(Control::Resume).unwrap();
+        let mut animation = Animation::new(simulation.clone()).with_frame_delay(Duration::ZERO);
+        animation.play(&mut output, &receiver).unwrap();
+
+        assert!(animation.simulation().is_finished());
+        assert!(String::from_utf8(output).unwrap().ends_with("Step 4/4: move 1 from 1 to 2\n"));
+
+        controls.send(Control::Stop).unwrap();
+        let mut animation = Animation::new(simulation);
+        animation.play(&mut io::sink(), &receiver).unwrap();
+
+        assert_eq!(0, animation.simulation().step());
+    }
+}
@@ -0,0 +1,129 @@
+pub trait Crane {
+    // How many of the crates still waiting to be moved the crane picks up in its next lift
+    fn lift_size(&self, num_crates_left: usize) -> usize;
+
+    // Lets restacking, and stack representations that can reverse crates lazily, skip
+    // going lift by lift when every move lands the same way. Has to agree with lift_size.
+    fn restacking(&self) -> Restacking {
+        Restacking::ByLifts
+    }
+
+    // Puts crates taken off the top of a stack, listed bottom first, into the order they end
+    // up in on the target stack. Each lift keeps its crates' order but lands on the last one.
+    fn restack<T>(&self, crates: &mut Vec<T>) where Self: Sized {
+        match self.restacking() {
+            Restacking::Unchanged => {}
+            Restacking::Reversed => crates.reverse(),
+            Restacking::ByLifts => {
+                let mut restacked = Vec::with_capacity(crates.len());
+                for lift_size in lift_sizes(self, crates.len()) {
+                    restacked.append(&mut crates.split_off(crates.len() - lift_size))
+                }
+                *crates = restacked
+            }
+        }
+    }
+}
+
+impl<C: Crane + ?Sized> Crane for &C {
+    fn lift_size(&self, num_crates_left: usize) -> usize {
+        (**self).lift_size(num_crates_left)
+    }
+
+    fn restacking(&self) -> Restacking {
+        (**self).restacking()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Restacking {
+    Unchanged,
+    Reversed,
+    ByLifts
+}
+
+// Sizes of the lifts a crane splits a move into, each at least one crate so every move finishes
+pub(crate) fn lift_sizes<C: Crane + ?Sized>(crane: &C, num_crates: usize) -> impl Iterator<Item = usize> + '_ {
+    let mut num_crates_left = num_crates;
+    std::iter::from_fn(move || {
+        if num_crates_left == 0 {
+            return None
+        }
+        let lift_size = crane.lift_size(num_crates_left).clamp(1, num_crates_left);
+        num_crates_left -= lift_size;
+        Some(lift_size)
+    })
+}
+
+// Moves one crate at a time, so a move lands its crates in reverse
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn lift_size(&self, _num_crates_left: usize) -> usize {
+        1
+    }
+
+    fn restacking(&self) -> Restacking {
+        Restacking::Reversed
+    }
+}
+
+// Moves every crate at once, so a move keeps its crates in order
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn lift_size(&self, num_crates_left: usize) -> usize {
+        num_crates_left
+    }
+
+    fn restacking(&self) -> Restacking {
+        Restacking::Unchanged
+    }
+}
+
+// Can't pick up more than a set number of crates, so big moves are split into chunks
+// taken from the top, each one landing in order on the one before
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LiftLimitedCrane {
+    max_lift: usize
+}
+
+impl LiftLimitedCrane {
+    // None when the crane couldn't lift anything
+    pub fn new(max_lift: usize) -> Option<Self> {
+        (max_lift > 0).then_some(LiftLimitedCrane { max_lift })
+    }
+
+    pub fn max_lift(&self) -> usize {
+        self.max_lift
+    }
+}
+
+impl Crane for LiftLimitedCrane {
+    fn lift_size(&self, num_crates_left: usize) -> usize {
+        num_crates_left.min(self.max_lift)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restacks_crates_by_lift() {
+        fn restack(crane: impl Crane) -> String {
+            let mut crates: Vec<_> = "abcdefg".chars().collect();
+            crane.restack(&mut crates);
+            crates.into_iter().collect()
+        }
+
+        assert_eq!("gfedcba", restack(CrateMover9000));
+        assert_eq!("abcdefg", restack(CrateMover9001));
+        assert_eq!("efgbcda", restack(LiftLimitedCrane::new(3).unwrap()));
+        assert_eq!(restack(CrateMover9000), restack(LiftLimitedCrane::new(1).unwrap()));
+        assert_eq!(restack(CrateMover9001), restack(LiftLimitedCrane::new(7).unwrap()));
+        assert_eq!(None, LiftLimitedCrane::new(0));
+    }
+}
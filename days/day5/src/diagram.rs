@@ -0,0 +1,260 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+pub fn parse_stacks(input: &str) -> Result<Vec<Vec<char>>, ParseDiagramError> {
+    parse_crate_stacks(input)
+}
+
+// Reads a drawing of crate stacks with a footer numbering each stack, giving every stack
+// from the bottom crate up. Cells are found by their place on a grid as wide as the first
+// crate's cell rather than by the footer's digits, so labels of any width line up, and
+// lines that stop early or have lost their trailing spaces just have empty cells at the
+// end. Crates can be wider than [A], like [AB] or [12:5], with every cell as wide as the
+// widest crate and labels padded with spaces.
+pub fn parse_crate_stacks<T: FromStr>(input: &str) -> Result<Vec<Vec<T>>, ParseDiagramError> {
+    let lines: Vec<&str> = input.lines().collect();
+    let footer_index = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .ok_or(ParseDiagramError::MissingFooter)?;
+    let num_stacks = parse_labels(lines[footer_index], footer_index + 1)?;
+    let cell_width = lines[..footer_index]
+        .iter()
+        .find_map(|line| {
+            let (_, cell) = line.split_once('[')?;
+            let (label, _) = cell.split_once(']')?;
+            Some(label.chars().count() + 2)
+        })
+        .unwrap_or(3)
+        .max(3);
+    let mut stacks: Vec<_> = (0..num_stacks).map(|_| Vec::new()).collect();
+    let mut has_gap = vec![false; num_stacks];
+    for (line_index, line) in lines[..footer_index].iter().enumerate().rev() {
+        let chars: Vec<char> = line.chars().collect();
+        for has_gap in has_gap.iter_mut().skip(chars.len().div_ceil(cell_width + 1)) {
+            *has_gap = true
+        }
+        for (i, cell) in chars.chunks(cell_width + 1).enumerate() {
+            let line = line_index + 1;
+            let column = (cell_width + 1) * i + 1;
+            let (cell, separator) = cell.split_at(cell.len().min(cell_width));
+            if let Some(&separator) = separator.first().filter(|separator| **separator != ' ') {
+                return Err(ParseDiagramError::MalformedCell { line, column: column + cell_width, found: separator.to_string() })
+            }
+            let label = match cell {
+                ['[', label @ .., ']'] if cell.len() == cell_width && label.iter().any(|c| !c.is_whitespace()) => {
+                    label.iter().collect::<String>().trim().to_string()
+                }
+                cell if cell.iter().all(|c| *c == ' ') => {
+                    if let Some(has_gap) = has_gap.get_mut(i) {
+                        *has_gap = true
+                    }
+                    continue
+                }
+                cell => return Err(ParseDiagramError::MalformedCell { line, column, found: cell.iter().collect() })
+            };
+            match has_gap.get(i) {
+                None => return Err(ParseDiagramError::UnlabelledCrate { line, column, label }),
+                Some(true) => return Err(ParseDiagramError::FloatingCrate { line, column, label }),
+                Some(false) => match label.parse() {
+                    Ok(name) => stacks[i].push(name),
+                    Err(_) => return Err(ParseDiagramError::InvalidCrate { line, column, label })
+                }
+            }
+        }
+    }
+    Ok(stacks)
+}
+
+// Draws stacks the way the puzzle input does, so a parsed diagram renders back to the
+// same text. Cells widen to fit the widest crate and labels are centered in them,
+// leaning left when that can't be exact.
+pub fn render_stacks<T: Display>(stacks: &[Vec<T>]) -> String {
+    let label_width = label_width(stacks);
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+    let rows = (0..height).rev().map(|level| stacks
+        .iter()
+        .map(|stack| render_cell(stack.get(level), label_width))
+        .collect::<Vec<_>>()
+        .join(" ")
+    );
+    rows.chain([render_footer(stacks.len(), label_width)]).collect::<Vec<_>>().join("\n")
+}
+
+pub(crate) fn label_width<T: Display>(stacks: &[Vec<T>]) -> usize {
+    stacks
+        .iter()
+        .flatten()
+        .map(|name| name.to_string().chars().count())
+        .max()
+        .unwrap_or(1)
+        .max(1)
+}
+
+pub(crate) fn render_cell<T: Display>(name: Option<&T>, label_width: usize) -> String {
+    match name {
+        Some(name) => format!("[{:^width$}]", name.to_string(), width = label_width),
+        None => " ".repeat(label_width + 2)
+    }
+}
+
+pub(crate) fn render_footer(num_stacks: usize, label_width: usize) -> String {
+    (1..=num_stacks)
+        .map(|label| format!("{:^width$}", label, width = label_width + 2))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// The footer has to number the stacks 1, 2, 3 and so on, returning how many there are
+fn parse_labels(footer: &str, line: usize) -> Result<usize, ParseDiagramError> {
+    let mut num_labels = 0;
+    let mut rest = footer;
+    while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+        let label_length = rest[start..].find(char::is_whitespace).unwrap_or(rest.len() - start);
+        let label = &rest[start..start + label_length];
+        num_labels += 1;
+        if label.parse() != Ok(num_labels) {
+            return Err(ParseDiagramError::InvalidLabel {
+                line,
+                column: footer[..footer.len() - rest.len() + start].chars().count() + 1,
+                expected: num_labels,
+                found: label.to_string()
+            })
+        }
+        rest = &rest[start + label_length..];
+    }
+    Ok(num_labels)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseDiagramError {
+    MissingFooter,
+    InvalidLabel { line: usize, column: usize, expected: usize, found: String },
+    MalformedCell { line: usize, column: usize, found: String },
+    UnlabelledCrate { line: usize, column: usize, label: String },
+    FloatingCrate { line: usize, column: usize, label: String },
+    InvalidCrate { line: usize, column: usize, label: String }
+}
+
+impl ParseDiagramError {
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            ParseDiagramError::MissingFooter => None,
+            ParseDiagramError::InvalidLabel { line, column, .. }
+            | ParseDiagramError::MalformedCell { line, column, .. }
+            | ParseDiagramError::UnlabelledCrate { line, column, .. }
+            | ParseDiagramError::FloatingCrate { line, column, .. }
+            | ParseDiagramError::InvalidCrate { line, column, .. } => Some((*line, *column))
+        }
+    }
+}
+
+impl Display for ParseDiagramError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some((line, column)) = self.position() {
+            write!(f, "Line {}, column {}: ", line, column)?
+        }
+        match self {
+            ParseDiagramError::MissingFooter => write!(f, "The diagram has no footer numbering its stacks"),
+            ParseDiagramError::InvalidLabel { expected, found, .. } => write!(f, "Expected stack label {} but found '{}'", expected, found),
+            ParseDiagramError::MalformedCell { found, .. } => write!(f, "Expected a crate like [A] or blank space but found '{}'", found),
+            ParseDiagramError::UnlabelledCrate { label, .. } => write!(f, "Crate {} is not above any numbered stack", label),
+            ParseDiagramError::FloatingCrate { label, .. } => write!(f, "Crate {} has nothing underneath it", label),
+            ParseDiagramError::InvalidCrate { label, .. } => write!(f, "'{}' is not a valid crate", label)
+        }
+    }
+}
+
+impl Error for ParseDiagramError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_example_diagram() {
+        let (diagram, _) = include_str!("example.txt").split_once("\n\n").unwrap();
+
+        let stacks = parse_stacks(diagram).unwrap();
+
+        assert_eq!(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']], stacks);
+    }
+
+    #[test]
+    fn renders_stacks_back_into_their_diagrams() {
+        for input in [include_str!("example.txt"), include_str!("actual.txt")] {
+            let (diagram, _) = input.split_once("\n\n").unwrap();
+
+            let stacks = parse_stacks(diagram).unwrap();
+
+            assert_eq!(diagram, render_stacks(&stacks));
+        }
+    }
+
+    #[test]
+    fn renders_empty_and_numerous_stacks() {
+        let mut stacks = vec![Vec::new(); 11];
+        stacks[10].push('K');
+
+        let diagram = render_stacks(&stacks);
+
+        assert_eq!(
+            [
+                "                                        [K]",
+                " 1   2   3   4   5   6   7   8   9  10  11 "
+            ].join("\n"),
+            diagram
+        );
+        assert_eq!(stacks, parse_stacks(&diagram).unwrap());
+    }
+
+    #[test]
+    fn parses_more_than_nine_stacks() {
+        let diagram = [
+            "                                        [K]",
+            "[A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [L]",
+            " 1   2   3   4   5   6   7   8   9  10  11 "
+        ].join("\n");
+
+        let stacks = parse_stacks(&diagram).unwrap();
+
+        assert_eq!(11, stacks.len());
+        assert_eq!(vec!['J'], stacks[9]);
+        assert_eq!(vec!['L', 'K'], stacks[10]);
+    }
+
+    #[test]
+    fn parses_lines_with_trailing_spaces_trimmed() {
+        let diagram = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n";
+
+        let stacks = parse_stacks(diagram).unwrap();
+
+        assert_eq!(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']], stacks);
+    }
+
+    #[test]
+    fn reports_position_of_malformed_diagrams() {
+        assert_eq!(Err(ParseDiagramError::MissingFooter), parse_stacks("\n\n"));
+        assert_eq!(
+            Err(ParseDiagramError::InvalidLabel { line: 2, column: 6, expected: 2, found: "3".to_string() }),
+            parse_stacks("[A] [B]\n 1   3 ")
+        );
+        assert_eq!(
+            Err(ParseDiagramError::MalformedCell { line: 1, column: 5, found: "[B".to_string() }),
+            parse_stacks("[A] [B\n 1   2 ")
+        );
+        assert_eq!(
+            Err(ParseDiagramError::MalformedCell { line: 1, column: 4, found: "-".to_string() }),
+            parse_stacks("[A]-[B]\n 1   2 ")
+        );
+        assert_eq!(
+            Err(ParseDiagramError::UnlabelledCrate { line: 1, column: 5, label: "B".to_string() }),
+            parse_stacks("[A] [B]\n 1 ")
+        );
+        assert_eq!(
+            Err(ParseDiagramError::FloatingCrate { line: 1, column: 5, label: "B".to_string() }),
+            parse_stacks("    [B]\n[A]\n 1   2 ")
+        );
+    }
+}
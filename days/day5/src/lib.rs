@@ -1,24 +1,71 @@
 // https://adventofcode.com/2022/day/5
 
-pub fn rearrange_creates(mut crate_stacks: Vec<Vec<char>>, operations: Vec<Operation>) -> Vec<Vec<char>> {
+mod animation;
+mod crane;
+mod diagram;
+mod operation;
+mod optimizer;
+mod simulation;
+mod stacks;
+mod storage;
+mod synthesis;
+mod typed_crate;
+
+pub use animation::{Animation, Control};
+pub use crane::{Crane, CrateMover9000, CrateMover9001, LiftLimitedCrane, Restacking};
+pub use diagram::{parse_crate_stacks, parse_stacks, render_stacks, ParseDiagramError};
+pub use operation::{parse_operations, validate_operations, Operation, OperationError, ParseOperationError};
+pub use optimizer::{optimize_plan, OptimizedPlan};
+pub use simulation::{Simulation, Step};
+pub use stacks::Stacks;
+pub use storage::{ChunkedStacks, CrateStacks};
+pub use synthesis::{synthesize_plan, Goal, Infeasibility, PlanSynthesis};
+pub use typed_crate::{Crate, ParseCrateError};
+
+pub fn rearrange_creates<T>(crate_stacks: Vec<Vec<T>>, operations: Vec<Operation>) -> Vec<Vec<T>> {
+    rearrange_crates_with(crate_stacks, operations, &CrateMover9000)
+}
+
+pub fn rearrange_creates_without_reversal<T>(crate_stacks: Vec<Vec<T>>, operations: Vec<Operation>) -> Vec<Vec<T>> {
+    rearrange_crates_with(crate_stacks, operations, &CrateMover9001)
+}
+
+// Panics with the failing operation's error when one can't run, see try_rearrange_crates_with
+pub fn rearrange_crates_with<T>(crate_stacks: Vec<Vec<T>>, operations: Vec<Operation>, crane: &impl Crane) -> Vec<Vec<T>> {
+    try_rearrange_crates_with(crate_stacks, operations, crane).unwrap_or_else(|error| panic!("{}", error))
+}
+
+// Runs the procedure on whichever representation suits the stacks, see ChunkedStacks
+pub fn rearrange_crates_in<S: CrateStacks>(crate_stacks: Vec<Vec<S::Crate>>, operations: Vec<Operation>, crane: &impl Crane) -> Vec<Vec<S::Crate>> {
+    let mut crate_stacks = S::from_stacks(crate_stacks);
     for operation in operations {
-        let source_stack = &mut crate_stacks[operation.source_stack_index];
-        let start_index_to_split = source_stack.len() - operation.num_crates_to_move;
-        let mut crates = source_stack.split_off(start_index_to_split);
-        crates.reverse();
-        crate_stacks[operation.target_stack_index].append(&mut crates)
+        crate_stacks.move_crates(&operation, crane)
     }
-    crate_stacks
+    crate_stacks.into_stacks()
 }
 
-pub fn rearrange_creates_without_reversal(mut crate_stacks: Vec<Vec<char>>, operations: Vec<Operation>) -> Vec<Vec<char>> {
-    for operation in operations {
-        let source_stack = &mut crate_stacks[operation.source_stack_index];
-        let start_index_to_split = source_stack.len() - operation.num_crates_to_move;
-        let mut crates = source_stack.split_off(start_index_to_split);
-        crate_stacks[operation.target_stack_index].append(&mut crates)
+pub(crate) fn move_crates<T>(crate_stacks: &mut [Vec<T>], operation: &Operation, crane: &impl Crane) {
+    let source_stack = &mut crate_stacks[operation.source_stack_index];
+    let start_index_to_split = source_stack.len() - operation.num_crates_to_move;
+    let mut crates = source_stack.split_off(start_index_to_split);
+    crane.restack(&mut crates);
+    crate_stacks[operation.target_stack_index].append(&mut crates)
+}
+
+// Leaves the stacks untouched when the operation can't run
+pub fn try_move_crates<T>(crate_stacks: &mut [Vec<T>], operation: &Operation, crane: &impl Crane) -> Result<(), OperationError> {
+    operation.check(crate_stacks.len(), |stack_index| crate_stacks[stack_index].len())?;
+    move_crates(crate_stacks, operation, crane);
+    Ok(())
+}
+
+// Checks each operation as it runs and reports the first one that can't, numbered from 1.
+// Use validate_operations to check the whole plan before moving anything.
+pub fn try_rearrange_crates_with<T>(mut crate_stacks: Vec<Vec<T>>, operations: Vec<Operation>, crane: &impl Crane) -> Result<Vec<Vec<T>>, OperationError> {
+    for (i, operation) in operations.iter().enumerate() {
+        try_move_crates(&mut crate_stacks, operation, crane).map_err(|error| error.at_line(i + 1))?
     }
-    crate_stacks
+    Ok(crate_stacks)
 }
 
 #[cfg(test)]
@@ -31,9 +78,7 @@ mod tests {
 
         let crate_stacks = rearrange_creates(crate_stacks, operations);
 
-        let top_crates = get_tops_of_crate_stacks(crate_stacks);
-
-        assert_eq!("CMZ", top_crates.iter().collect::<String>());
+        assert_eq!("CMZ", Stacks::from(crate_stacks).answer(""));
     }
     
     #[test]
@@ -42,9 +87,7 @@ mod tests {
 
         let crate_stacks = rearrange_creates(crate_stacks, operations);
 
-        let top_crates = get_tops_of_crate_stacks(crate_stacks);
-
-        assert_eq!("CVCWCRTVQ", top_crates.iter().collect::<String>());
+        assert_eq!("CVCWCRTVQ", Stacks::from(crate_stacks).answer(""));
     }
 
     #[test]
@@ -53,9 +96,7 @@ mod tests {
 
         let crate_stacks = rearrange_creates_without_reversal(crate_stacks, operations);
 
-        let top_crates = get_tops_of_crate_stacks(crate_stacks);
-
-        assert_eq!("MCD", top_crates.iter().collect::<String>());
+        assert_eq!("MCD", Stacks::from(crate_stacks).answer(""));
     }
 
     #[test]
@@ -64,70 +105,47 @@ mod tests {
 
         let crate_stacks = rearrange_creates_without_reversal(crate_stacks, operations);
 
-        let top_crates = get_tops_of_crate_stacks(crate_stacks);
-
-        assert_eq!("CNSCZWLVT", top_crates.iter().collect::<String>());
+        assert_eq!("CNSCZWLVT", Stacks::from(crate_stacks).answer(""));
     }
 
-    fn get_tops_of_crate_stacks(crate_stacks: Vec<Vec<char>>) -> Vec<char> {
-        crate_stacks
-            .iter()
-            .map(|stack| *stack.last().unwrap())
-            .collect()
+    #[test]
+    fn rearranges_crates_with_lift_limited_crane_for_example_input() {
+        let (crate_stacks, operations) = parse_input(include_str!("example.txt"));
+
+        let crate_stacks = rearrange_crates_with(crate_stacks, operations, &LiftLimitedCrane::new(2).unwrap());
+
+        assert_eq!(vec![vec!['M'], vec!['C'], vec!['P', 'N', 'D', 'Z']], crate_stacks);
     }
 
-    fn parse_input(input: &str) -> (Vec<Vec<char>>, Vec<Operation>) {
-        let (stacks, operations) = input.split_once("\n\n").unwrap();
-        (parse_stacks(stacks), parse_operations(operations))
+    #[test]
+    fn reports_operation_that_cannot_run() {
+        let (crate_stacks, mut operations) = parse_input(include_str!("example.txt"));
+        operations.push("move 5 from 3 to 1".parse().unwrap());
+
+        let result = try_rearrange_crates_with(crate_stacks, operations, &CrateMover9001);
+
+        assert_eq!(
+            Err(OperationError::NotEnoughCrates { line: Some(5), stack: 3, requested: 5, available: 4 }),
+            result
+        );
     }
 
-    fn parse_stacks(input: &str) -> Vec<Vec<char>> {
-        let num_stacks = input
-            .lines()
-            .last()
-            .unwrap()
-            .chars()
-            .filter(|c| c.is_numeric())
-            .last()
-            .unwrap()
-            .to_digit(10)
-            .unwrap();
-        let mut stacks: Vec<_> = (0..num_stacks)
-            .map(|_| Vec::new())
-            .collect();
-        for line in input.lines().take(input.lines().count() - 1) {
-            for (i, _crate) in line.chars().collect::<Vec<char>>().chunks(4).enumerate() {
-                let crate_name = _crate[1];
-                if crate_name.is_alphabetic() {
-                    stacks[i].insert(0, crate_name)
-                }
-            }
-        }
-        stacks
+    #[test]
+    fn moves_crates_only_when_the_operation_can_run() {
+        let (mut crate_stacks, _) = parse_input(include_str!("example.txt"));
+
+        let missing_stack = try_move_crates(&mut crate_stacks, &Operation::new(1, 3, 0), &CrateMover9000);
+        let not_enough_crates = try_move_crates(&mut crate_stacks, &Operation::new(2, 2, 0), &CrateMover9000);
+        let moved = try_move_crates(&mut crate_stacks, &Operation::new(1, 2, 0), &CrateMover9000);
+
+        assert_eq!(Err(OperationError::MissingStack { line: None, stack: 4, num_stacks: 3 }), missing_stack);
+        assert_eq!(Err(OperationError::NotEnoughCrates { line: None, stack: 3, requested: 2, available: 1 }), not_enough_crates);
+        assert_eq!(Ok(()), moved);
+        assert_eq!(vec![vec!['Z', 'N', 'P'], vec!['M', 'C', 'D'], vec![]], crate_stacks);
     }
 
-    fn parse_operations(input: &str) -> Vec<Operation> {
-        input
-            .lines()
-            .map(|line| {
-                let numbers: Vec<_> = line
-                    .split_ascii_whitespace()
-                    .map(|word| word.parse::<usize>())
-                    .filter(|maybe_num| maybe_num.is_ok())
-                    .map(|num| num.unwrap())
-                    .collect();
-                Operation {
-                    num_crates_to_move: numbers[0],
-                    source_stack_index: numbers[1] - 1,
-                    target_stack_index: numbers[2] - 1
-                }
-            })
-            .collect()
+    pub(crate) fn parse_input(input: &str) -> (Vec<Vec<char>>, Vec<Operation>) {
+        let (stacks, operations) = input.split_once("\n\n").unwrap();
+        (parse_stacks(stacks).unwrap(), parse_operations(operations).unwrap())
     }
 }
-
-pub struct Operation {
-    num_crates_to_move: usize,
-    source_stack_index: usize,
-    target_stack_index: usize
-}
\ No newline at end of file
@@ -0,0 +1,235 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+// Stack indices are 0 based, unlike the 1 based stack numbers written in the procedure
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Operation {
+    pub(crate) num_crates_to_move: usize,
+    pub(crate) source_stack_index: usize,
+    pub(crate) target_stack_index: usize
+}
+
+impl Operation {
+    pub fn new(num_crates_to_move: usize, source_stack_index: usize, target_stack_index: usize) -> Self {
+        Operation {
+            num_crates_to_move,
+            source_stack_index,
+            target_stack_index
+        }
+    }
+
+    pub fn num_crates_to_move(&self) -> usize {
+        self.num_crates_to_move
+    }
+
+    pub fn source_stack_index(&self) -> usize {
+        self.source_stack_index
+    }
+
+    pub fn target_stack_index(&self) -> usize {
+        self.target_stack_index
+    }
+
+    pub(crate) fn check(&self, num_stacks: usize, height: impl Fn(usize) -> usize) -> Result<(), OperationError> {
+        for stack_index in [self.source_stack_index, self.target_stack_index] {
+            if stack_index >= num_stacks {
+                return Err(OperationError::MissingStack {
+                    line: None,
+                    stack: stack_index + 1,
+                    num_stacks
+                })
+            }
+        }
+        let available = height(self.source_stack_index);
+        if self.num_crates_to_move > available {
+            return Err(OperationError::NotEnoughCrates {
+                line: None,
+                stack: self.source_stack_index + 1,
+                requested: self.num_crates_to_move,
+                available
+            })
+        }
+        Ok(())
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.num_crates_to_move,
+            self.source_stack_index + 1,
+            self.target_stack_index + 1
+        )
+    }
+}
+
+// Reads the procedure's move 1 from 2 to 1 notation
+impl FromStr for Operation {
+    type Err = ParseOperationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let malformed = || ParseOperationError::MalformedOperation {
+            line: None,
+            value: value.to_string()
+        };
+        let words: Vec<_> = value.split_ascii_whitespace().collect();
+        let ["move", num_crates_to_move, "from", source_stack, "to", target_stack] = words.as_slice() else {
+            return Err(malformed())
+        };
+        let num_crates_to_move = num_crates_to_move.parse().map_err(|_| malformed())?;
+        let stack_index = |stack: &str| match stack.parse::<usize>() {
+            Ok(0) => Err(ParseOperationError::InvalidStack { line: None, stack: 0 }),
+            Ok(stack) => Ok(stack - 1),
+            Err(_) => Err(malformed())
+        };
+        Ok(Operation {
+            num_crates_to_move,
+            source_stack_index: stack_index(source_stack)?,
+            target_stack_index: stack_index(target_stack)?
+        })
+    }
+}
+
+pub fn parse_operations(input: &str) -> Result<Vec<Operation>, ParseOperationError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| line
+            .parse()
+            .map_err(|error: ParseOperationError| error.at_line(i + 1))
+        )
+        .collect()
+}
+
+// Checks every operation against the stacks' heights as they would be when it runs, so
+// a plan can be rejected before any crate moves. Which crane runs the plan doesn't
+// matter since cranes only change the order crates land in.
+pub fn validate_operations<T>(crate_stacks: &[Vec<T>], operations: &[Operation]) -> Result<(), OperationError> {
+    let mut heights: Vec<_> = crate_stacks.iter().map(|stack| stack.len()).collect();
+    for (i, operation) in operations.iter().enumerate() {
+        operation
+            .check(heights.len(), |stack_index| heights[stack_index])
+            .map_err(|error| error.at_line(i + 1))?;
+        heights[operation.source_stack_index] -= operation.num_crates_to_move;
+        heights[operation.target_stack_index] += operation.num_crates_to_move
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseOperationError {
+    MalformedOperation { line: Option<usize>, value: String },
+    InvalidStack { line: Option<usize>, stack: usize }
+}
+
+impl ParseOperationError {
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ParseOperationError::MalformedOperation { line, .. }
+            | ParseOperationError::InvalidStack { line, .. } => *line
+        }
+    }
+
+    pub fn at_line(mut self, line_number: usize) -> Self {
+        match &mut self {
+            ParseOperationError::MalformedOperation { line, .. }
+            | ParseOperationError::InvalidStack { line, .. } => *line = Some(line_number)
+        }
+        self
+    }
+}
+
+impl Display for ParseOperationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line() {
+            write!(f, "Line {}: ", line)?
+        }
+        match self {
+            ParseOperationError::MalformedOperation { value, .. } => write!(f, "Expected an operation like move 1 from 2 to 1 but found '{}'", value),
+            ParseOperationError::InvalidStack { stack, .. } => write!(f, "There is no stack {}, stacks are numbered from 1", stack)
+        }
+    }
+}
+
+impl Error for ParseOperationError {}
+
+// Lines count operations from 1, the way they're listed in the procedure, and stacks
+// are the procedure's 1 based stack numbers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OperationError {
+    MissingStack { line: Option<usize>, stack: usize, num_stacks: usize },
+    NotEnoughCrates { line: Option<usize>, stack: usize, requested: usize, available: usize }
+}
+
+impl OperationError {
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            OperationError::MissingStack { line, .. } | OperationError::NotEnoughCrates { line, .. } => *line
+        }
+    }
+
+    pub fn at_line(mut self, line_number: usize) -> Self {
+        match &mut self {
+            OperationError::MissingStack { line, .. } | OperationError::NotEnoughCrates { line, .. } => *line = Some(line_number)
+        }
+        self
+    }
+}
+
+impl Display for OperationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line() {
+            write!(f, "Operation {}: ", line)?
+        }
+        match self {
+            OperationError::MissingStack { stack, num_stacks, .. } => write!(f, "There is no stack {} among {} stacks", stack, num_stacks),
+            OperationError::NotEnoughCrates { stack, requested, available, .. } => write!(f, "Can't move {} crates off stack {} which only has {}", requested, stack, available)
+        }
+    }
+}
+
+impl Error for OperationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_displays_operations() {
+        let operation: Operation = "move 3 from 1 to 12".parse().unwrap();
+
+        assert_eq!(Operation::new(3, 0, 11), operation);
+        assert_eq!("move 3 from 1 to 12", operation.to_string());
+    }
+
+    #[test]
+    fn fails_to_parse_malformed_operations() {
+        assert_eq!(
+            Err(ParseOperationError::MalformedOperation { line: Some(2), value: "move x from 1 to 2".to_string() }),
+            parse_operations("move 1 from 2 to 1\nmove x from 1 to 2")
+        );
+        assert_eq!(
+            Err(ParseOperationError::InvalidStack { line: Some(1), stack: 0 }),
+            parse_operations("move 1 from 0 to 1")
+        );
+    }
+
+    #[test]
+    fn validates_operations_against_stack_heights() {
+        let crate_stacks = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
+        let operations = parse_operations("move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 3 from 1 to 2").unwrap();
+
+        assert_eq!(Ok(()), validate_operations(&crate_stacks, &operations[..2]));
+        assert_eq!(
+            Err(OperationError::NotEnoughCrates { line: Some(3), stack: 1, requested: 3, available: 0 }),
+            validate_operations(&crate_stacks, &operations)
+        );
+        assert_eq!(
+            Err(OperationError::MissingStack { line: Some(1), stack: 4, num_stacks: 3 }),
+            validate_operations(&crate_stacks, &[Operation::new(1, 0, 3)])
+        );
+    }
+}
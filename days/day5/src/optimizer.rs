@@ -0,0 +1,155 @@
+use crate::{move_crates, validate_operations, Crane, Operation, OperationError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizedPlan {
+    operations: Vec<Operation>,
+    num_moves_saved: usize
+}
+
+impl OptimizedPlan {
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    pub fn into_operations(self) -> Vec<Operation> {
+        self.operations
+    }
+
+    pub fn num_moves_saved(&self) -> usize {
+        self.num_moves_saved
+    }
+}
+
+// Shortens a plan by dropping operations that leave the stacks as they were and by
+// folding pairs of operations on the same stacks into one or none. Every rewrite is
+// checked by simulating both versions from the arrangement the plan has reached, so
+// the shorter plan leaves these stacks exactly as the original would with this crane,
+// though not necessarily any others.
+pub fn optimize_plan<T: Clone + Eq>(crate_stacks: &[Vec<T>], operations: &[Operation], crane: &impl Crane) -> Result<OptimizedPlan, OperationError> {
+    validate_operations(crate_stacks, operations)?;
+    let mut optimized = operations.to_vec();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        let mut state = crate_stacks.to_vec();
+        let mut i = 0;
+        while i < optimized.len() {
+            if let Some(rewrite) = find_rewrite(&state, &optimized, i, crane) {
+                if let Some(partner_index) = rewrite.partner_index {
+                    optimized.remove(partner_index);
+                }
+                optimized.splice(i..=i, rewrite.replacement);
+                changed = true;
+                continue
+            }
+            move_crates(&mut state, &optimized[i], crane);
+            i += 1
+        }
+    }
+    debug_assert!(simulate(crate_stacks, operations, crane) == simulate(crate_stacks, &optimized, crane));
+    Ok(OptimizedPlan {
+        num_moves_saved: operations.len() - optimized.len(),
+        operations: optimized
+    })
+}
+
+struct Rewrite {
+    // The later operation folded into the one being rewritten
+    partner_index: Option<usize>,
+    replacement: Option<Operation>
+}
+
+fn find_rewrite<T: Clone + Eq>(state: &[Vec<T>], operations: &[Operation], i: usize, crane: &impl Crane) -> Option<Rewrite> {
+    let operation = operations[i];
+    if simulate(state, &[operation], crane) == state {
+        return Some(Rewrite { partner_index: None, replacement: None })
+    }
+    // The next operation using either of this one's stacks can be brought alongside it
+    // as long as nothing in between touches its stacks either
+    let touches = |other: &Operation, stack_index: usize| other.source_stack_index == stack_index || other.target_stack_index == stack_index;
+    let partner_index = (i + 1..operations.len()).find(|&j| {
+        touches(&operations[j], operation.source_stack_index) || touches(&operations[j], operation.target_stack_index)
+    })?;
+    let partner = operations[partner_index];
+    if operations[i + 1..partner_index]
+        .iter()
+        .any(|other| touches(other, partner.source_stack_index) || touches(other, partner.target_stack_index)) {
+        return None
+    }
+    let expected = simulate(state, &[operation, partner], crane);
+    if expected == state {
+        return Some(Rewrite { partner_index: Some(partner_index), replacement: None })
+    }
+    let num_crates = [operation.num_crates_to_move, partner.num_crates_to_move, operation.num_crates_to_move + partner.num_crates_to_move];
+    let stacks = [
+        (operation.source_stack_index, operation.target_stack_index),
+        (operation.source_stack_index, partner.target_stack_index),
+        (partner.source_stack_index, partner.target_stack_index)
+    ];
+    num_crates
+        .into_iter()
+        .flat_map(|num_crates| stacks.map(|(source, target)| Operation::new(num_crates, source, target)))
+        .find(|candidate| candidate.num_crates_to_move <= state[candidate.source_stack_index].len()
+            && simulate(state, &[*candidate], crane) == expected)
+        .map(|candidate| Rewrite {
+            partner_index: Some(partner_index),
+            replacement: Some(candidate)
+        })
+}
+
+fn simulate<T: Clone>(crate_stacks: &[Vec<T>], operations: &[Operation], crane: &impl Crane) -> Vec<Vec<T>> {
+    let mut crate_stacks = crate_stacks.to_vec();
+    for operation in operations {
+        move_crates(&mut crate_stacks, operation, crane)
+    }
+    crate_stacks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::parse_input;
+    use crate::{parse_operations, CrateMover9000, CrateMover9001};
+
+    #[test]
+    fn drops_moves_that_cancel_out() {
+        let crate_stacks = vec![vec!['A', 'B'], vec!['C'], vec!['D'], vec![]];
+        let operations = parse_operations("move 2 from 1 to 2\nmove 1 from 3 to 4\nmove 2 from 2 to 1\nmove 0 from 2 to 3").unwrap();
+
+        let plan = optimize_plan(&crate_stacks, &operations, &CrateMover9001).unwrap();
+
+        assert_eq!(vec![Operation::new(1, 2, 3)], plan.operations());
+        assert_eq!(3, plan.num_moves_saved());
+    }
+
+    #[test]
+    fn merges_moves_depending_on_crane() {
+        let crate_stacks = vec![vec!['A', 'B', 'C'], vec![]];
+        let operations = parse_operations("move 1 from 1 to 2\nmove 2 from 1 to 2").unwrap();
+
+        let crate_mover_9000_plan = optimize_plan(&crate_stacks, &operations, &CrateMover9000).unwrap();
+        let crate_mover_9001_plan = optimize_plan(&crate_stacks, &operations, &CrateMover9001).unwrap();
+
+        assert_eq!(vec![Operation::new(3, 0, 1)], crate_mover_9000_plan.operations());
+        assert_eq!(operations, crate_mover_9001_plan.operations());
+    }
+
+    #[test]
+    fn keeps_actual_procedure_equivalent() {
+        let (crate_stacks, operations) = parse_input(include_str!("actual.txt"));
+
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+            let plan = optimize_plan(&crate_stacks, &operations, &crane).unwrap();
+
+            assert_eq!(operations.len() - plan.operations().len(), plan.num_moves_saved());
+            assert_eq!(simulate(&crate_stacks, &operations, &crane), simulate(&crate_stacks, plan.operations(), &crane));
+        }
+    }
+
+    #[test]
+    fn rejects_plans_that_cannot_run() {
+        let result = optimize_plan(&[vec!['A']], &[Operation::new(1, 0, 1)], &CrateMover9000);
+
+        assert_eq!(Err(OperationError::MissingStack { line: Some(1), stack: 2, num_stacks: 1 }), result);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{move_crates, validate_operations, Crane, Operation, OperationError};
+
+// Runs a procedure one operation at a time. Each applied operation remembers the crates
+// it took off the source stack, which is enough to undo it without keeping a copy of the
+// stacks for every step.
+#[derive(Debug, Clone)]
+pub struct Simulation<C: Crane, T = char> {
+    crate_stacks: Vec<Vec<T>>,
+    operations: Vec<Operation>,
+    crane: C,
+    applied_steps: Vec<Step<T>>
+}
+
+impl<C: Crane, T: Clone> Simulation<C, T> {
+    // The whole plan is validated up front so stepping through it can't fail
+    pub fn new(crate_stacks: Vec<Vec<T>>, operations: Vec<Operation>, crane: C) -> Result<Self, OperationError> {
+        validate_operations(&crate_stacks, &operations)?;
+        Ok(Simulation {
+            crate_stacks,
+            operations,
+            crane,
+            applied_steps: Vec::new()
+        })
+    }
+
+    pub fn crate_stacks(&self) -> &[Vec<T>] {
+        &self.crate_stacks
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    pub fn crane(&self) -> &C {
+        &self.crane
+    }
+
+    // How many operations have been applied, from 0 before the first up to num_steps at the end
+    pub fn step(&self) -> usize {
+        self.applied_steps.len()
+    }
+
+    pub fn num_steps(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step() == self.num_steps()
+    }
+
+    pub fn applied_steps(&self) -> &[Step<T>] {
+        &self.applied_steps
+    }
+
+    // Gives the operation that was applied or None when already at the end
+    pub fn step_forward(&mut self) -> Option<Operation> {
+        let operation = *self.operations.get(self.step())?;
+        let source_stack = &self.crate_stacks[operation.source_stack_index];
+        let lifted_crates = source_stack[source_stack.len() - operation.num_crates_to_move..].to_vec();
+        move_crates(&mut self.crate_stacks, &operation, &self.crane);
+        self.applied_steps.push(Step { operation, lifted_crates });
+        Some(operation)
+    }
+
+    // Gives the operation that was undone or None when already at the start
+    pub fn step_back(&mut self) -> Option<Operation> {
+        let Step { operation, lifted_crates } = self.applied_steps.pop()?;
+        let target_stack = &mut self.crate_stacks[operation.target_stack_index];
+        target_stack.truncate(target_stack.len() - operation.num_crates_to_move);
+        self.crate_stacks[operation.source_stack_index].extend(lifted_crates);
+        Some(operation)
+    }
+
+    // Steps forwards or backwards until the given number of operations have been applied,
+    // staying put and returning false when there aren't that many operations
+    pub fn jump_to(&mut self, step: usize) -> bool {
+        if step > self.num_steps() {
+            return false
+        }
+        while self.step() < step {
+            self.step_forward();
+        }
+        while self.step() > step {
+            self.step_back();
+        }
+        true
+    }
+
+    pub fn run_to_end(&mut self) -> &[Vec<T>] {
+        self.jump_to(self.num_steps());
+        &self.crate_stacks
+    }
+
+    pub fn into_crate_stacks(self) -> Vec<Vec<T>> {
+        self.crate_stacks
+    }
+}
+
+// An applied operation along with the crates it lifted, listed bottom first as they were on the source stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step<T = char> {
+    operation: Operation,
+    lifted_crates: Vec<T>
+}
+
+impl<T> Step<T> {
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    pub fn lifted_crates(&self) -> &[T] {
+        &self.lifted_crates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::parse_input;
+    use crate::{rearrange_creates, CrateMover9000};
+
+    #[test]
+    fn steps_forwards_and_backwards() {
+        let mut simulation = example_simulation();
+        let initial_stacks = simulation.crate_stacks().to_vec();
+
+        assert_eq!(Some(Operation::new(1, 1, 0)), simulation.step_forward());
+        let stacks_after_first_step = simulation.crate_stacks().to_vec();
+        assert_eq!(vec![vec!['Z', 'N', 'D'], vec!['M', 'C'], vec!['P']], stacks_after_first_step);
+        simulation.step_forward();
+        assert_eq!(['Z', 'N', 'D'], simulation.applied_steps()[1].lifted_crates());
+
+        assert_eq!(Some(Operation::new(3, 0, 2)), simulation.step_back());
+        assert_eq!(stacks_after_first_step, simulation.crate_stacks());
+        simulation.step_back();
+        assert_eq!(initial_stacks, simulation.crate_stacks());
+        assert_eq!(None, simulation.step_back());
+    }
+
+    #[test]
+    fn jumps_to_any_step() {
+        let mut simulation = example_simulation();
+        let (crate_stacks, operations) = parse_input(include_str!("example.txt"));
+        let final_stacks = rearrange_creates(crate_stacks.clone(), operations);
+
+        assert_eq!(final_stacks, simulation.run_to_end());
+        assert!(simulation.is_finished());
+        assert_eq!(None, simulation.step_forward());
+        assert!(!simulation.jump_to(5));
+        assert!(simulation.jump_to(0));
+        assert_eq!(crate_stacks, simulation.crate_stacks());
+        assert!(simulation.jump_to(4));
+        assert_eq!(final_stacks, simulation.into_crate_stacks());
+    }
+
+    #[test]
+    fn undoes_moves_onto_the_same_stack() {
+        let mut simulation = Simulation::new(vec![vec!['A', 'B', 'C']], vec![Operation::new(2, 0, 0)], CrateMover9000).unwrap();
+
+        simulation.step_forward();
+        assert_eq!(vec![vec!['A', 'C', 'B']], simulation.crate_stacks());
+        simulation.step_back();
+        assert_eq!(vec![vec!['A', 'B', 'C']], simulation.crate_stacks());
+    }
+
+    #[test]
+    fn rejects_plans_that_cannot_run() {
+        let result = Simulation::new(vec![vec!['A']], vec![Operation::new(2, 0, 0)], CrateMover9000);
+
+        assert_eq!(
+            Some(OperationError::NotEnoughCrates { line: Some(1), stack: 1, requested: 2, available: 1 }),
+            result.err()
+        );
+    }
+
+    fn example_simulation() -> Simulation<CrateMover9000> {
+        let (crate_stacks, operations) = parse_input(include_str!("example.txt"));
+        Simulation::new(crate_stacks, operations, CrateMover9000).unwrap()
+    }
+}
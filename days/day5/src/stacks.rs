@@ -0,0 +1,128 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{parse_crate_stacks, render_stacks, ParseDiagramError};
+
+// Crate stacks listed left to right, each from its bottom crate up, that can be asked
+// about without assuming every stack still has a crate on it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Stacks<T = char>(Vec<Vec<T>>);
+
+impl<T> Stacks<T> {
+    pub fn new(crate_stacks: Vec<Vec<T>>) -> Self {
+        Stacks(crate_stacks)
+    }
+
+    pub fn num_stacks(&self) -> usize {
+        self.0.len()
+    }
+
+    // None when there's no such stack
+    pub fn contents(&self, stack_index: usize) -> Option<&[T]> {
+        self.0.get(stack_index).map(Vec::as_slice)
+    }
+
+    // None when there's no such stack
+    pub fn height(&self, stack_index: usize) -> Option<usize> {
+        self.0.get(stack_index).map(Vec::len)
+    }
+
+    pub fn heights(&self) -> Vec<usize> {
+        self.0.iter().map(Vec::len).collect()
+    }
+
+    // None when the stack is empty or there's no such stack
+    pub fn top(&self, stack_index: usize) -> Option<&T> {
+        self.0.get(stack_index)?.last()
+    }
+
+    pub fn tops(&self) -> Vec<Option<&T>> {
+        self.0.iter().map(|stack| stack.last()).collect()
+    }
+
+    pub fn into_inner(self) -> Vec<Vec<T>> {
+        self.0
+    }
+}
+
+impl<T: Display> Stacks<T> {
+    // The puzzle's answer of every top crate in a row. Empty stacks show up as the
+    // placeholder, so an empty string leaves them out and a space keeps each crate in line
+    // with its stack.
+    pub fn answer(&self, empty_stack_placeholder: &str) -> String {
+        self.tops()
+            .into_iter()
+            .map(|top| match top {
+                Some(name) => name.to_string(),
+                None => empty_stack_placeholder.to_string()
+            })
+            .collect()
+    }
+}
+
+impl<T> From<Vec<Vec<T>>> for Stacks<T> {
+    fn from(crate_stacks: Vec<Vec<T>>) -> Self {
+        Stacks(crate_stacks)
+    }
+}
+
+impl<T> From<Stacks<T>> for Vec<Vec<T>> {
+    fn from(stacks: Stacks<T>) -> Self {
+        stacks.0
+    }
+}
+
+impl<T> AsRef<[Vec<T>]> for Stacks<T> {
+    fn as_ref(&self) -> &[Vec<T>] {
+        &self.0
+    }
+}
+
+// Draws the stacks as a diagram, see render_stacks
+impl<T: Display> Display for Stacks<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", render_stacks(&self.0))
+    }
+}
+
+// Reads a diagram, see parse_crate_stacks
+impl<T: FromStr> FromStr for Stacks<T> {
+    type Err = ParseDiagramError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_crate_stacks(value).map(Stacks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rearrange_creates, Operation};
+
+    #[test]
+    fn answers_with_placeholder_for_empty_stacks() {
+        let stacks: Stacks = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ".parse().unwrap();
+
+        let stacks = Stacks::from(rearrange_creates(stacks.into_inner(), vec![Operation::new(1, 2, 0)]));
+
+        assert_eq!(vec![Some(&'P'), Some(&'D'), None], stacks.tops());
+        assert_eq!("PD", stacks.answer(""));
+        assert_eq!("PD ", stacks.answer(" "));
+        assert_eq!("PD_", stacks.answer("_"));
+    }
+
+    #[test]
+    fn queries_stack_heights_and_contents() {
+        let stacks = Stacks::new(vec![vec!['Z', 'N'], vec![], vec!['P']]);
+
+        assert_eq!(3, stacks.num_stacks());
+        assert_eq!(vec![2, 0, 1], stacks.heights());
+        assert_eq!(Some(2), stacks.height(0));
+        assert_eq!(None, stacks.height(3));
+        assert_eq!(Some(&['Z', 'N'][..]), stacks.contents(0));
+        assert_eq!(Some(&[][..]), stacks.contents(1));
+        assert_eq!(None, stacks.top(1));
+        assert_eq!(None, stacks.top(3));
+        assert_eq!("[N]        \n[Z]     [P]\n 1   2   3 ", stacks.to_string());
+    }
+}
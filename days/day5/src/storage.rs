@@ -0,0 +1,302 @@
+use std::rc::Rc;
+
+use crate::crane::lift_sizes;
+use crate::{Crane, Operation, Restacking};
+
+// Somewhere to keep crate stacks while a procedure runs. Stacks go in and come out as
+// plain vectors, bottom crate first, so representations can be swapped freely.
+pub trait CrateStacks {
+    type Crate;
+
+    fn from_stacks(crate_stacks: Vec<Vec<Self::Crate>>) -> Self;
+
+    fn into_stacks(self) -> Vec<Vec<Self::Crate>>;
+
+    fn num_stacks(&self) -> usize;
+
+    fn height(&self, stack_index: usize) -> usize;
+
+    fn top(&self, stack_index: usize) -> Option<&Self::Crate>;
+
+    // Panics like slice indexing when the operation can't run, see validate_operations
+    fn move_crates(&mut self, operation: &Operation, crane: &impl Crane);
+}
+
+impl<T> CrateStacks for Vec<Vec<T>> {
+    type Crate = T;
+
+    fn from_stacks(crate_stacks: Vec<Vec<T>>) -> Self {
+        crate_stacks
+    }
+
+    fn into_stacks(self) -> Vec<Vec<T>> {
+        self
+    }
+
+    fn num_stacks(&self) -> usize {
+        self.len()
+    }
+
+    fn height(&self, stack_index: usize) -> usize {
+        self[stack_index].len()
+    }
+
+    fn top(&self, stack_index: usize) -> Option<&T> {
+        self[stack_index].last()
+    }
+
+    fn move_crates(&mut self, operation: &Operation, crane: &impl Crane) {
+        crate::move_crates(self, operation, crane)
+    }
+}
+
+// Keeps each stack as a list of chunks that share their crates and remember whether they
+// are upside down. Moving crates only moves chunks around, splitting at most one, and
+// reversing a move reverses the order of its chunks and flips each one, so a move costs
+// the number of chunks it takes rather than the number of crates. Stacks that have
+// broken up into many small chunks are copied back into one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkedStacks<T = char> {
+    stacks: Vec<Vec<Chunk<T>>>,
+    heights: Vec<usize>
+}
+
+// Copying a crate is far cheaper than handling a chunk, so stacks are compacted well
+// before their chunks get small
+const MIN_CHUNKS_TO_COMPACT: usize = 16;
+const MIN_AVERAGE_CHUNK_LENGTH: usize = 1024;
+
+impl<T: Clone> ChunkedStacks<T> {
+    fn take_top(&mut self, stack_index: usize, num_crates: usize) -> Vec<Chunk<T>> {
+        self.heights[stack_index] -= num_crates;
+        take_top(&mut self.stacks[stack_index], num_crates)
+    }
+
+    fn put_on_top(&mut self, stack_index: usize, chunks: Vec<Chunk<T>>) {
+        let stack = &mut self.stacks[stack_index];
+        for chunk in chunks {
+            self.heights[stack_index] += chunk.len();
+            match stack.last_mut().and_then(|top| top.joined_with(&chunk)) {
+                Some(joined) => *stack.last_mut().unwrap() = joined,
+                None => stack.push(chunk)
+            }
+        }
+        if stack.len() >= MIN_CHUNKS_TO_COMPACT && self.heights[stack_index] < stack.len() * MIN_AVERAGE_CHUNK_LENGTH {
+            *stack = vec![Chunk::new(concat(stack))]
+        }
+    }
+}
+
+impl<T: Clone> CrateStacks for ChunkedStacks<T> {
+    type Crate = T;
+
+    fn from_stacks(crate_stacks: Vec<Vec<T>>) -> Self {
+        ChunkedStacks {
+            heights: crate_stacks.iter().map(|stack| stack.len()).collect(),
+            stacks: crate_stacks
+                .into_iter()
+                .map(|stack| if stack.is_empty() { Vec::new() } else { vec![Chunk::new(stack)] })
+                .collect()
+        }
+    }
+
+    fn into_stacks(self) -> Vec<Vec<T>> {
+        self.stacks
+            .iter()
+            .map(|chunks| concat(chunks))
+            .collect()
+    }
+
+    fn num_stacks(&self) -> usize {
+        self.stacks.len()
+    }
+
+    fn height(&self, stack_index: usize) -> usize {
+        self.heights[stack_index]
+    }
+
+    fn top(&self, stack_index: usize) -> Option<&T> {
+        self.stacks[stack_index].last().map(Chunk::top)
+    }
+
+    fn move_crates(&mut self, operation: &Operation, crane: &impl Crane) {
+        let height = self.heights[operation.source_stack_index];
+        assert!(
+            operation.num_crates_to_move <= height,
+            "Can't move {} crates off a stack of {}",
+            operation.num_crates_to_move,
+            height
+        );
+        let mut lifted = self.take_top(operation.source_stack_index, operation.num_crates_to_move);
+        match crane.restacking() {
+            Restacking::Unchanged => self.put_on_top(operation.target_stack_index, lifted),
+            Restacking::Reversed => {
+                lifted.reverse();
+                lifted.iter_mut().for_each(Chunk::reverse);
+                self.put_on_top(operation.target_stack_index, lifted)
+            }
+            Restacking::ByLifts => {
+                for lift_size in lift_sizes(crane, operation.num_crates_to_move) {
+                    let lift = take_top(&mut lifted, lift_size);
+                    self.put_on_top(operation.target_stack_index, lift)
+                }
+            }
+        }
+    }
+}
+
+fn concat<T: Clone>(chunks: &[Chunk<T>]) -> Vec<T> {
+    let mut crates = Vec::with_capacity(chunks.iter().map(Chunk::len).sum());
+    for chunk in chunks {
+        let run = &chunk.crates[chunk.start..chunk.end];
+        if chunk.reversed {
+            crates.extend(run.iter().rev().cloned())
+        } else {
+            crates.extend_from_slice(run)
+        }
+    }
+    crates
+}
+
+// Takes the given number of crates off the top of a list of chunks, bottom chunk first
+fn take_top<T>(chunks: &mut Vec<Chunk<T>>, num_crates: usize) -> Vec<Chunk<T>> {
+    let height: usize = chunks.iter().map(Chunk::len).sum();
+    let index = chunk_index_at(chunks, height - num_crates);
+    chunks.split_off(index)
+}
+
+// Splits chunks as needed so one starts exactly that many crates from the bottom, giving its index
+fn chunk_index_at<T>(chunks: &mut Vec<Chunk<T>>, num_crates_below: usize) -> usize {
+    let mut crates_below = 0;
+    for i in 0..chunks.len() {
+        if crates_below == num_crates_below {
+            return i
+        }
+        let len = chunks[i].len();
+        if crates_below + len > num_crates_below {
+            let (bottom, top) = chunks[i].split(num_crates_below - crates_below);
+            chunks[i] = bottom;
+            chunks.insert(i + 1, top);
+            return i + 1
+        }
+        crates_below += len
+    }
+    chunks.len()
+}
+
+// A run of shared crates, read bottom first from start to end or, when reversed, from end to start
+#[derive(Debug, PartialEq, Eq)]
+struct Chunk<T> {
+    crates: Rc<[T]>,
+    start: usize,
+    end: usize,
+    reversed: bool
+}
+
+// Sharing the crates doesn't need them to be Clone
+impl<T> Clone for Chunk<T> {
+    fn clone(&self) -> Self {
+        Chunk {
+            crates: Rc::clone(&self.crates),
+            ..*self
+        }
+    }
+}
+
+impl<T> Chunk<T> {
+    fn new(crates: Vec<T>) -> Self {
+        Chunk {
+            end: crates.len(),
+            crates: crates.into(),
+            start: 0,
+            reversed: false
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    fn top(&self) -> &T {
+        if self.reversed { &self.crates[self.start] } else { &self.crates[self.end - 1] }
+    }
+
+    fn reverse(&mut self) {
+        self.reversed = !self.reversed
+    }
+
+    // The bottom num_crates crates and the rest
+    fn split(&self, num_crates: usize) -> (Chunk<T>, Chunk<T>) {
+        let middle = if self.reversed { self.end - num_crates } else { self.start + num_crates };
+        let lower = Chunk { start: self.start, end: middle, ..self.clone() };
+        let upper = Chunk { start: middle, end: self.end, ..self.clone() };
+        if self.reversed { (upper, lower) } else { (lower, upper) }
+    }
+
+    // Undoes a split when the chunk on top carries on where this one left off
+    fn joined_with(&self, above: &Chunk<T>) -> Option<Chunk<T>> {
+        if !Rc::ptr_eq(&self.crates, &above.crates) || self.reversed != above.reversed {
+            return None
+        }
+        match self.reversed {
+            false if self.end == above.start => Some(Chunk { end: above.end, ..self.clone() }),
+            true if above.end == self.start => Some(Chunk { start: above.start, ..self.clone() }),
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::parse_input;
+    use crate::{rearrange_crates_in, rearrange_crates_with, CrateMover9000, CrateMover9001, LiftLimitedCrane};
+
+    #[test]
+    fn matches_vectors_for_every_crane() {
+        let (crate_stacks, operations) = parse_input(include_str!("actual.txt"));
+        let lift_limited_crane = LiftLimitedCrane::new(3).unwrap();
+
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001, &lift_limited_crane] {
+            assert_eq!(
+                rearrange_crates_with(crate_stacks.clone(), operations.clone(), &crane),
+                rearrange_crates_in::<ChunkedStacks>(crate_stacks.clone(), operations.clone(), &crane)
+            );
+        }
+    }
+
+    #[test]
+    fn moves_crates_back_and_forth_without_losing_order() {
+        let crate_stacks = vec!["ABCDEFGH".chars().collect(), vec![], vec!['X']];
+        let mut chunked_stacks = ChunkedStacks::from_stacks(crate_stacks.clone());
+        let mut vector_stacks = crate_stacks;
+
+        for (num_crates, source, target) in [(5, 0, 1), (2, 1, 2), (3, 2, 0), (4, 0, 1), (2, 1, 1), (5, 1, 2)] {
+            chunked_stacks.move_crates(&Operation::new(num_crates, source, target), &CrateMover9000);
+            vector_stacks.move_crates(&Operation::new(num_crates, source, target), &CrateMover9000);
+
+            for stack_index in 0..3 {
+                assert_eq!(vector_stacks.height(stack_index), chunked_stacks.height(stack_index));
+                assert_eq!(vector_stacks.top(stack_index), chunked_stacks.top(stack_index));
+            }
+        }
+
+        assert_eq!(vector_stacks, chunked_stacks.into_stacks());
+    }
+
+    #[test]
+    fn compacts_fragmented_stacks() {
+        let crate_stacks = vec![(0..100).map(|i| char::from(b'0' + i % 10)).collect(), vec![]];
+        let mut chunked_stacks = ChunkedStacks::from_stacks(crate_stacks.clone());
+
+        for _ in 0..50 {
+            chunked_stacks.move_crates(&Operation::new(2, 0, 1), &CrateMover9000);
+        }
+
+        assert!(chunked_stacks.stacks[1].len() < MIN_CHUNKS_TO_COMPACT);
+        assert_eq!(
+            rearrange_crates_with(crate_stacks, vec![Operation::new(2, 0, 1); 50], &CrateMover9000),
+            chunked_stacks.into_stacks()
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::{move_crates, Crane, Operation};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Goal<T = char> {
+    // Every stack exactly as given, bottom crate first
+    Stacks(Vec<Vec<T>>),
+    // The crate that should end up on top of each stack, None meaning the stack ends up empty
+    Tops(Vec<Option<T>>)
+}
+
+impl<T: PartialEq> Goal<T> {
+    pub fn is_reached_by(&self, crate_stacks: &[Vec<T>]) -> bool {
+        match self {
+            Goal::Stacks(stacks) => stacks == crate_stacks,
+            Goal::Tops(tops) => tops.len() == crate_stacks.len() && tops
+                .iter()
+                .zip(crate_stacks)
+                .all(|(top, stack)| top.as_ref() == stack.last())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanSynthesis<T = char> {
+    // As few operations as the crane allows
+    Found(Vec<Operation>),
+    Impossible(Infeasibility<T>),
+    // The state limit ran out before a plan was found
+    Inconclusive
+}
+
+impl<T> PlanSynthesis<T> {
+    pub fn operations(&self) -> Option<&[Operation]> {
+        match self {
+            PlanSynthesis::Found(operations) => Some(operations),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Infeasibility<T = char> {
+    WrongNumberOfStacks { expected: usize, found: usize },
+    // The goal needs crates, sorted, that the stacks don't have or leaves out crates the stacks do have
+    MismatchedCrates { missing: Vec<T>, left_over: Vec<T> },
+    // Every arrangement the crane can reach was tried
+    Unreachable
+}
+
+// A breadth first search over arrangements, so the first plan found is a shortest one.
+// Crates can't appear or disappear, which rules many goals out before searching, and
+// max_states bounds how many arrangements are expanded when a goal is far away.
+pub fn synthesize_plan<T: Clone + Ord + Hash>(crate_stacks: &[Vec<T>], goal: &Goal<T>, crane: &impl Crane, max_states: Option<usize>) -> PlanSynthesis<T> {
+    if let Err(infeasibility) = check_crates(crate_stacks, goal) {
+        return PlanSynthesis::Impossible(infeasibility)
+    }
+    if goal.is_reached_by(crate_stacks) {
+        return PlanSynthesis::Found(Vec::new())
+    }
+    let mut predecessors = Predecessors::new();
+    predecessors.insert(crate_stacks.to_vec(), None);
+    let mut queue = VecDeque::from([crate_stacks.to_vec()]);
+    let mut num_states = 0;
+    while let Some(state) = queue.pop_front() {
+        if max_states.is_some_and(|max_states| num_states >= max_states) {
+            return PlanSynthesis::Inconclusive
+        }
+        num_states += 1;
+        for operation in possible_operations(&state) {
+            let mut next_state = state.clone();
+            move_crates(&mut next_state, &operation, crane);
+            if predecessors.contains_key(&next_state) {
+                continue
+            }
+            let is_goal = goal.is_reached_by(&next_state);
+            predecessors.insert(next_state.clone(), Some((state.clone(), operation)));
+            if is_goal {
+                return PlanSynthesis::Found(trace_operations(&predecessors, next_state))
+            }
+            queue.push_back(next_state)
+        }
+    }
+    PlanSynthesis::Impossible(Infeasibility::Unreachable)
+}
+
+// Each arrangement maps to the one it was reached from and the operation that got there
+type Predecessors<T> = HashMap<Vec<Vec<T>>, Option<(Vec<Vec<T>>, Operation)>>;
+
+fn check_crates<T: Clone + Ord>(crate_stacks: &[Vec<T>], goal: &Goal<T>) -> Result<(), Infeasibility<T>> {
+    let (num_stacks, mut needed_crates) = match goal {
+        Goal::Stacks(stacks) => (stacks.len(), stacks.concat()),
+        Goal::Tops(tops) => (tops.len(), tops.iter().flatten().cloned().collect())
+    };
+    if num_stacks != crate_stacks.len() {
+        return Err(Infeasibility::WrongNumberOfStacks {
+            expected: num_stacks,
+            found: crate_stacks.len()
+        })
+    }
+    let mut left_over = crate_stacks.concat();
+    left_over.sort_unstable();
+    needed_crates.sort_unstable();
+    let mut missing = Vec::new();
+    for name in needed_crates {
+        match left_over.binary_search(&name) {
+            Ok(i) => {
+                left_over.remove(i);
+            }
+            Err(_) => missing.push(name)
+        }
+    }
+    if let Goal::Tops(tops) = goal {
+        // Crates nobody asked for can go anywhere as long as a stack is allowed to hold them
+        if tops.iter().any(Option::is_some) {
+            left_over.clear()
+        }
+    }
+    if missing.is_empty() && left_over.is_empty() {
+        Ok(())
+    } else {
+        Err(Infeasibility::MismatchedCrates { missing, left_over })
+    }
+}
+
+// Moves onto the same stack are included since a crane that reorders crates can use them
+fn possible_operations<T>(crate_stacks: &[Vec<T>]) -> impl Iterator<Item = Operation> + '_ {
+    (0..crate_stacks.len()).flat_map(move |source| (0..crate_stacks.len()).flat_map(move |target| {
+        (1..=crate_stacks[source].len()).map(move |num_crates| Operation::new(num_crates, source, target))
+    }))
+}
+
+fn trace_operations<T: Clone + Eq + Hash>(predecessors: &Predecessors<T>, mut state: Vec<Vec<T>>) -> Vec<Operation> {
+    let mut operations = Vec::new();
+    while let Some(Some((previous_state, operation))) = predecessors.get(&state) {
+        operations.push(*operation);
+        state = previous_state.clone()
+    }
+    operations.reverse();
+    operations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::parse_input;
+    use crate::{rearrange_crates_with, CrateMover9000, CrateMover9001};
+
+    #[test]
+    fn finds_short_plan_reaching_stacks() {
+        let (crate_stacks, operations) = parse_input(include_str!("example.txt"));
+        let goal = Goal::Stacks(rearrange_crates_with(crate_stacks.clone(), operations, &CrateMover9000));
+
+        let synthesis = synthesize_plan(&crate_stacks, &goal, &CrateMover9000, None);
+
+        let plan = synthesis.operations().unwrap().to_vec();
+        assert!(plan.len() <= 4);
+        assert!(goal.is_reached_by(&rearrange_crates_with(crate_stacks, plan, &CrateMover9000)));
+    }
+
+    #[test]
+    fn finds_shortest_plan_reaching_tops() {
+        let (crate_stacks, _) = parse_input(include_str!("example.txt"));
+        let goal = Goal::Tops(vec![Some('P'), None, Some('N')]);
+
+        let synthesis = synthesize_plan(&crate_stacks, &goal, &CrateMover9001, None);
+
+        let plan = synthesis.operations().unwrap().to_vec();
+        assert_eq!(3, plan.len());
+        assert!(goal.is_reached_by(&rearrange_crates_with(crate_stacks, plan, &CrateMover9001)));
+    }
+
+    #[test]
+    fn reports_impossible_goals() {
+        let (crate_stacks, _) = parse_input(include_str!("example.txt"));
+
+        assert_eq!(
+            PlanSynthesis::Impossible(Infeasibility::WrongNumberOfStacks { expected: 2, found: 3 }),
+            synthesize_plan(&crate_stacks, &Goal::Tops(vec![Some('Z'), None]), &CrateMover9000, None)
+        );
+        assert_eq!(
+            PlanSynthesis::Impossible(Infeasibility::MismatchedCrates { missing: vec!['X'], left_over: vec![] }),
+            synthesize_plan(&crate_stacks, &Goal::Tops(vec![Some('X'), None, None]), &CrateMover9000, None)
+        );
+        assert_eq!(
+            PlanSynthesis::Impossible(Infeasibility::MismatchedCrates { missing: vec![], left_over: vec!['D', 'M', 'N', 'P', 'Z'] }),
+            synthesize_plan(&crate_stacks, &Goal::Stacks(vec![vec!['C'], vec![], vec![]]), &CrateMover9000, None)
+        );
+        assert_eq!(
+            PlanSynthesis::Impossible(Infeasibility::Unreachable),
+            synthesize_plan(&[vec!['A', 'B']], &Goal::Stacks(vec![vec!['B', 'A']]), &CrateMover9001, None)
+        );
+    }
+
+    #[test]
+    fn gives_up_when_out_of_states() {
+        let (crate_stacks, operations) = parse_input(include_str!("example.txt"));
+        let goal = Goal::Stacks(rearrange_crates_with(crate_stacks.clone(), operations, &CrateMover9000));
+
+        assert_eq!(PlanSynthesis::Inconclusive, synthesize_plan(&crate_stacks, &goal, &CrateMover9000, Some(1)));
+    }
+}
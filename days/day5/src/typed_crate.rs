@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+// A crate labelled with any number of characters and maybe weighed, written AB or AB:12
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Crate {
+    label: String,
+    weight: Option<u32>
+}
+
+impl Crate {
+    // Labels can't be empty or hold whitespace, brackets or colons since those give diagrams their shape
+    pub fn new(label: &str, weight: Option<u32>) -> Result<Self, ParseCrateError> {
+        if label.is_empty() || label.chars().any(|c| c.is_whitespace() || matches!(c, '[' | ']' | ':')) {
+            return Err(ParseCrateError::InvalidLabel(label.to_string()))
+        }
+        Ok(Crate {
+            label: label.to_string(),
+            weight
+        })
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn weight(&self) -> Option<u32> {
+        self.weight
+    }
+}
+
+impl Display for Crate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.weight {
+            Some(weight) => write!(f, "{}:{}", self.label, weight),
+            None => write!(f, "{}", self.label)
+        }
+    }
+}
+
+impl FromStr for Crate {
+    type Err = ParseCrateError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            Some((label, weight)) => {
+                let weight = weight.parse().map_err(|_| ParseCrateError::InvalidWeight(weight.to_string()))?;
+                Crate::new(label, Some(weight))
+            }
+            None => Crate::new(value, None)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCrateError {
+    InvalidLabel(String),
+    InvalidWeight(String)
+}
+
+impl Display for ParseCrateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCrateError::InvalidLabel(label) => write!(f, "'{}' is not a crate label", label),
+            ParseCrateError::InvalidWeight(weight) => write!(f, "'{}' is not a weight", weight)
+        }
+    }
+}
+
+impl Error for ParseCrateError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_crate_stacks, rearrange_creates, render_stacks, Operation, ParseDiagramError};
+
+    #[test]
+    fn parses_and_displays_crates() {
+        let weighed: Crate = "AB:12".parse().unwrap();
+
+        assert_eq!("AB", weighed.label());
+        assert_eq!(Some(12), weighed.weight());
+        assert_eq!("AB:12", weighed.to_string());
+        assert_eq!(Ok(Crate::new("7", None).unwrap()), "7".parse());
+        assert_eq!(Err(ParseCrateError::InvalidWeight("x".to_string())), "A:x".parse::<Crate>());
+        assert_eq!(Err(ParseCrateError::InvalidLabel("".to_string())), "".parse::<Crate>());
+    }
+
+    #[test]
+    fn rearranges_stacks_of_wide_crates() {
+        let diagram = [
+            "       [C:3 ]",
+            "[A:12] [ B  ]",
+            "  1      2   "
+        ].join("\n");
+        let crate_stacks: Vec<Vec<Crate>> = parse_crate_stacks(&diagram).unwrap();
+
+        assert_eq!(Some(3), crate_stacks[1][1].weight());
+        assert_eq!(diagram, render_stacks(&crate_stacks));
+
+        let crate_stacks = rearrange_creates(crate_stacks, vec![Operation::new(2, 1, 0)]);
+
+        assert_eq!(
+            [
+                "[ B  ]       ",
+                "[C:3 ]       ",
+                "[A:12]       ",
+                "  1      2   "
+            ].join("\n"),
+            render_stacks(&crate_stacks)
+        );
+    }
+
+    #[test]
+    fn parses_numeric_labels() {
+        let crate_stacks: Vec<Vec<u32>> = parse_crate_stacks("     [7 ]\n[10] [12]\n 1    2  ").unwrap();
+
+        assert_eq!(vec![vec![10], vec![12, 7]], crate_stacks);
+        assert_eq!(
+            Err(ParseDiagramError::InvalidCrate { line: 1, column: 1, label: "x".to_string() }),
+            parse_crate_stacks::<u32>("[x ]\n 1  ")
+        );
+    }
+}